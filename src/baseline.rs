@@ -0,0 +1,325 @@
+use crate::strategy::{Canonical, Context, VelocityStrategy};
+use crate::swarm::Particle;
+use mikes_pso::vector::Vector;
+use rand::Rng;
+use std::f64::consts::{LN_2, PI};
+
+/// Every classic baseline with its standard parameters alongside the given
+/// canonical pso.
+pub fn baselines<const SIZE: usize>(canonical: Canonical) -> Vec<Box<dyn VelocityStrategy<SIZE>>> {
+    vec![
+        Box::new(canonical),
+        Box::new(Inertia::default()),
+        Box::new(Constriction::default()),
+        Box::new(LinearInertia::default()),
+        Box::new(BareBones),
+        Box::new(FullyInformed::default()),
+        Box::new(Spso2011::default()),
+    ]
+}
+
+/// Standard normal sample using the Box-Muller transform.
+pub fn gaussian(rng: &mut impl Rng) -> f64 {
+    let u1: f64 = 1.0 - rng.gen::<f64>();
+    let u2: f64 = rng.gen();
+
+    (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+}
+
+/// `w v + c1 r1 (p - x) + c2 r2 (g - x)` with random numbers drawn per
+/// dimension.
+fn inertia_update<const SIZE: usize>(
+    (w, c1, c2): (f64, f64, f64),
+    current: &Particle<SIZE>,
+    best: &Particle<SIZE>,
+) -> Vector<SIZE> {
+    let mut rng = rand::thread_rng();
+    let (x, v, p, g) = (
+        current.coordinates(),
+        current.velocity(),
+        current.best(),
+        best.best(),
+    );
+
+    Vector::from(std::array::from_fn(|d| {
+        w * v[d] + c1 * rng.gen::<f64>() * (p[d] - x[d]) + c2 * rng.gen::<f64>() * (g[d] - x[d])
+    }))
+}
+
+/// Inertia weight pso (Shi and Eberhart) with the parameters recommended by
+/// Eberhart and Shi (2000).
+#[derive(Debug, Clone, Copy)]
+pub struct Inertia {
+    pub w: f64,
+    pub c1: f64,
+    pub c2: f64,
+}
+
+impl Default for Inertia {
+    fn default() -> Self {
+        Self {
+            w: 0.729,
+            c1: 1.49445,
+            c2: 1.49445,
+        }
+    }
+}
+
+impl<const SIZE: usize> VelocityStrategy<SIZE> for Inertia {
+    fn name(&self) -> String {
+        "inertia".to_string()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        vec![("w", self.w), ("c1", self.c1), ("c2", self.c2)]
+    }
+
+    fn update(
+        &self,
+        current: &Particle<SIZE>,
+        best: &Particle<SIZE>,
+        _: &Context<SIZE>,
+    ) -> Vector<SIZE> {
+        inertia_update((self.w, self.c1, self.c2), current, best)
+    }
+}
+
+/// Constriction coefficient pso (Clerc and Kennedy, 2002).
+#[derive(Debug, Clone, Copy)]
+pub struct Constriction {
+    pub phi1: f64,
+    pub phi2: f64,
+}
+
+impl Default for Constriction {
+    fn default() -> Self {
+        Self {
+            phi1: 2.05,
+            phi2: 2.05,
+        }
+    }
+}
+
+impl Constriction {
+    /// `2 / |2 - phi - sqrt(phi^2 - 4 phi)|` for `phi = phi1 + phi2 > 4`.
+    pub fn chi(&self) -> f64 {
+        let phi = self.phi1 + self.phi2;
+        2.0 / (2.0 - phi - (phi * phi - 4.0 * phi).sqrt()).abs()
+    }
+}
+
+impl<const SIZE: usize> VelocityStrategy<SIZE> for Constriction {
+    fn name(&self) -> String {
+        "constriction".to_string()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("chi", self.chi()),
+            ("phi1", self.phi1),
+            ("phi2", self.phi2),
+        ]
+    }
+
+    fn update(
+        &self,
+        current: &Particle<SIZE>,
+        best: &Particle<SIZE>,
+        _: &Context<SIZE>,
+    ) -> Vector<SIZE> {
+        let chi = self.chi();
+        inertia_update((chi, chi * self.phi1, chi * self.phi2), current, best)
+    }
+}
+
+/// Inertia weight decreasing linearly from `start` to `end` over the run.
+#[derive(Debug, Clone, Copy)]
+pub struct LinearInertia {
+    pub start: f64,
+    pub end: f64,
+    pub c1: f64,
+    pub c2: f64,
+}
+
+impl Default for LinearInertia {
+    fn default() -> Self {
+        Self {
+            start: 0.9,
+            end: 0.4,
+            c1: 2.0,
+            c2: 2.0,
+        }
+    }
+}
+
+impl<const SIZE: usize> VelocityStrategy<SIZE> for LinearInertia {
+    fn name(&self) -> String {
+        "ldiw".to_string()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("w_start", self.start),
+            ("w_end", self.end),
+            ("c1", self.c1),
+            ("c2", self.c2),
+        ]
+    }
+
+    fn update(
+        &self,
+        current: &Particle<SIZE>,
+        best: &Particle<SIZE>,
+        context: &Context<SIZE>,
+    ) -> Vector<SIZE> {
+        let progress = (context.iteration as f64 / context.iterations.max(1) as f64).min(1.0);
+        let w = self.start - (self.start - self.end) * progress;
+        inertia_update((w, self.c1, self.c2), current, best)
+    }
+}
+
+/// Bare bones pso (Kennedy, 2003) which samples the next position from a
+/// gaussian centred between the personal and global best.
+#[derive(Debug, Clone, Copy)]
+pub struct BareBones;
+
+impl<const SIZE: usize> VelocityStrategy<SIZE> for BareBones {
+    fn name(&self) -> String {
+        "barebones".to_string()
+    }
+
+    fn update(
+        &self,
+        current: &Particle<SIZE>,
+        best: &Particle<SIZE>,
+        _: &Context<SIZE>,
+    ) -> Vector<SIZE> {
+        let mut rng = rand::thread_rng();
+        let (x, p, g) = (current.coordinates(), current.best(), best.best());
+
+        // the velocity is the step needed to land on the sampled position
+        Vector::from(std::array::from_fn(|d| {
+            let mean = (p[d] + g[d]) / 2.0;
+            let std = (p[d] - g[d]).abs();
+            mean + std * gaussian(&mut rng) - x[d]
+        }))
+    }
+}
+
+/// Fully informed pso (Mendes, Kennedy and Neves, 2004) where every particle
+/// is attracted to the personal best of every particle in its neighbourhood.
+#[derive(Debug, Clone, Copy)]
+pub struct FullyInformed {
+    pub chi: f64,
+    pub phi: f64,
+}
+
+impl Default for FullyInformed {
+    fn default() -> Self {
+        Self {
+            chi: 0.7298,
+            phi: 4.1,
+        }
+    }
+}
+
+impl<const SIZE: usize> VelocityStrategy<SIZE> for FullyInformed {
+    fn name(&self) -> String {
+        "fips".to_string()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        vec![("chi", self.chi), ("phi", self.phi)]
+    }
+
+    fn informed(&self) -> bool {
+        true
+    }
+
+    fn update(
+        &self,
+        current: &Particle<SIZE>,
+        _: &Particle<SIZE>,
+        context: &Context<SIZE>,
+    ) -> Vector<SIZE> {
+        let mut rng = rand::thread_rng();
+        let (x, v) = (current.coordinates(), current.velocity());
+        let k = context.neighbours.len() as f64;
+        let mut pull = [0.0; SIZE];
+        for neighbour in context.neighbours {
+            let p = neighbour.best();
+            for (d, pull) in pull.iter_mut().enumerate() {
+                *pull += rng.gen_range(0.0..self.phi) * (p[d] - x[d]) / k;
+            }
+        }
+
+        Vector::from(std::array::from_fn(|d| self.chi * (v[d] + pull[d])))
+    }
+}
+
+/// Standard pso 2011 (Clerc, 2012) which samples the next position uniformly
+/// from a hypersphere around the centre of gravity of `x`, `p` and `g`.
+#[derive(Debug, Clone, Copy)]
+pub struct Spso2011 {
+    pub w: f64,
+    pub c: f64,
+}
+
+impl Default for Spso2011 {
+    fn default() -> Self {
+        Self {
+            w: 1.0 / (2.0 * LN_2),
+            c: 0.5 + LN_2,
+        }
+    }
+}
+
+impl<const SIZE: usize> VelocityStrategy<SIZE> for Spso2011 {
+    fn name(&self) -> String {
+        "spso2011".to_string()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        vec![("w", self.w), ("c", self.c)]
+    }
+
+    fn update(
+        &self,
+        current: &Particle<SIZE>,
+        best: &Particle<SIZE>,
+        _: &Context<SIZE>,
+    ) -> Vector<SIZE> {
+        let mut rng = rand::thread_rng();
+        let (x, v, p, l) = (
+            current.coordinates(),
+            current.velocity(),
+            current.best(),
+            best.best(),
+        );
+
+        // the particle which is the best excludes the duplicate attractor
+        let own = p.iter().zip(l.iter()).all(|(p, l)| p == l);
+        let centre: [f64; SIZE] = std::array::from_fn(|d| {
+            let px = x[d] + self.c * (p[d] - x[d]);
+            if own {
+                (x[d] + px) / 2.0
+            } else {
+                let lx = x[d] + self.c * (l[d] - x[d]);
+                (x[d] + px + lx) / 3.0
+            }
+        });
+        let radius = (0..SIZE)
+            .map(|d| (centre[d] - x[d]).powi(2))
+            .sum::<f64>()
+            .sqrt();
+
+        // uniform sample in the hypersphere
+        let direction: [f64; SIZE] = std::array::from_fn(|_| gaussian(&mut rng));
+        let norm = direction.iter().map(|x| x * x).sum::<f64>().sqrt();
+        let scale = radius * rng.gen::<f64>().powf(1.0 / SIZE as f64) / norm;
+
+        Vector::from(std::array::from_fn(|d| {
+            self.w * v[d] + centre[d] + direction[d] * scale - x[d]
+        }))
+    }
+}
@@ -0,0 +1,702 @@
+use crate::function::Function;
+use mikes_pso::{bounds::Bound, vector::Vector};
+use std::f64::consts::PI;
+
+type Objective = Box<dyn Fn(&[f64]) -> f64 + Send>;
+
+/// Instances of the current COCO bbob suite.
+pub const INSTANCES: std::ops::RangeInclusive<usize> = 1..=15;
+
+/// The 24 noiseless bbob functions of the given instance, with the optimum
+/// location, optimal value and rotations of every instance generated as in
+/// the COCO bbob2009 legacy code so that `f{n}` of instance `i` is the same
+/// problem the COCO tooling expects. Every function is searched within
+/// `[-5, 5]` and has its minimum at `fopt`, stored as the minima.
+pub fn functions<const SIZE: usize>(instance: usize) -> Vec<Function<SIZE>> {
+    (1..=24).map(|f| function(f, instance)).collect()
+}
+
+/// Function `f` of the suite for the given instance.
+pub fn function<const SIZE: usize>(f: usize, instance: usize) -> Function<SIZE> {
+    let d = SIZE;
+    // functions 4 and 18 share the instances of functions 3 and 17
+    let rseed = match f {
+        4 => 3,
+        18 => 17,
+        f => f as i64,
+    } + 10000 * instance as i64;
+    let fopt = fopt(rseed);
+    let xopt = xopt(rseed, d);
+    let r = rotation(rseed + 1000000, d);
+    let q = rotation(rseed, d);
+    let optimum = optimum(f, rseed, &xopt, &q);
+
+    let (name, func): (&str, Objective) = match f {
+        1 => (
+            "sphere",
+            Box::new(move |x: &[f64]| sphere(&shift(x, &xopt))),
+        ),
+        2 => (
+            "ellipsoid",
+            Box::new(move |x: &[f64]| ellipsoid(&oscillate(shift(x, &xopt)))),
+        ),
+        3 => (
+            "rastrigin",
+            Box::new(move |x: &[f64]| {
+                let z = asymmetric(0.2, oscillate(shift(x, &xopt)));
+                rastrigin(&condition(10.0, z))
+            }),
+        ),
+        4 => {
+            let xopt = optimum.clone();
+            (
+                "bueche-rastrigin",
+                Box::new(move |x: &[f64]| {
+                    let z = oscillate(shift(x, &xopt))
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, z)| {
+                            let s = 10_f64.powf(0.5 * exponent(i, d));
+                            if i % 2 == 0 && z > 0.0 {
+                                10.0 * s * z
+                            } else {
+                                s * z
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                    rastrigin(&z) + 100.0 * penalty(x)
+                }),
+            )
+        }
+        5 => {
+            let xopt = optimum.clone();
+            (
+                "linear slope",
+                Box::new(move |x: &[f64]| {
+                    x.iter()
+                        .zip(&xopt)
+                        .enumerate()
+                        .map(|(i, (x, o))| {
+                            let s = o.signum() * 10_f64.powf(exponent(i, d));
+                            let z = if x * o < 25.0 { *x } else { *o };
+                            5.0 * s.abs() - s * z
+                        })
+                        .sum()
+                }),
+            )
+        }
+        6 => {
+            let m = product(&q, &scaled(10.0, &r));
+            (
+                "attractive sector",
+                Box::new(move |x: &[f64]| {
+                    let z = multiply(&m, &shift(x, &xopt));
+                    let sum = z
+                        .iter()
+                        .zip(&xopt)
+                        .map(|(z, o)| if z * o > 0.0 { 1e4 * z * z } else { z * z })
+                        .sum::<f64>();
+                    tosz(sum).powf(0.9)
+                }),
+            )
+        }
+        7 => {
+            let r = scaled(10.0, &r);
+            (
+                "step ellipsoid",
+                Box::new(move |x: &[f64]| {
+                    let z = multiply(&r, &shift(x, &xopt));
+                    let rounded = z
+                        .iter()
+                        .map(|z| {
+                            if z.abs() > 0.5 {
+                                (z + 0.5).floor()
+                            } else {
+                                (10.0 * z + 0.5).floor() / 10.0
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                    let sum = multiply(&q, &rounded)
+                        .iter()
+                        .enumerate()
+                        .map(|(i, z)| 100_f64.powf(exponent(i, d)) * z * z)
+                        .sum::<f64>();
+                    0.1 * (1e-4 * z[0].abs()).max(sum) + penalty(x)
+                }),
+            )
+        }
+        8 => {
+            let xopt = optimum.clone();
+            let factor = ((d as f64).sqrt() / 8.0).max(1.0);
+            (
+                "rosenbrock",
+                Box::new(move |x: &[f64]| {
+                    let z = shift(x, &xopt)
+                        .into_iter()
+                        .map(|z| factor * z + 1.0)
+                        .collect::<Vec<_>>();
+                    rosenbrock(&z)
+                }),
+            )
+        }
+        9 => {
+            let factor = ((d as f64).sqrt() / 8.0).max(1.0);
+            (
+                "rosenbrock rotated",
+                Box::new(move |x: &[f64]| {
+                    let z = multiply(&q, x)
+                        .into_iter()
+                        .map(|z| factor * z + 0.5)
+                        .collect::<Vec<_>>();
+                    rosenbrock(&z)
+                }),
+            )
+        }
+        10 => (
+            "ellipsoid rotated",
+            Box::new(move |x: &[f64]| ellipsoid(&oscillate(multiply(&r, &shift(x, &xopt))))),
+        ),
+        11 => (
+            "discus",
+            Box::new(move |x: &[f64]| {
+                let z = oscillate(multiply(&r, &shift(x, &xopt)));
+                1e6 * z[0] * z[0] + z[1..].iter().map(|z| z * z).sum::<f64>()
+            }),
+        ),
+        12 => (
+            "bent cigar",
+            Box::new(move |x: &[f64]| {
+                let z = multiply(&r, &asymmetric(0.5, multiply(&r, &shift(x, &xopt))));
+                z[0] * z[0] + 1e6 * z[1..].iter().map(|z| z * z).sum::<f64>()
+            }),
+        ),
+        13 => {
+            let m = product(&q, &scaled(10.0, &r));
+            (
+                "sharp ridge",
+                Box::new(move |x: &[f64]| {
+                    let z = multiply(&m, &shift(x, &xopt));
+                    z[0] * z[0] + 100.0 * z[1..].iter().map(|z| z * z).sum::<f64>().sqrt()
+                }),
+            )
+        }
+        14 => (
+            "different powers",
+            Box::new(move |x: &[f64]| {
+                multiply(&r, &shift(x, &xopt))
+                    .iter()
+                    .enumerate()
+                    .map(|(i, z)| z.abs().powf(2.0 + 4.0 * exponent(i, d)))
+                    .sum::<f64>()
+                    .sqrt()
+            }),
+        ),
+        15 => {
+            let m = product(&r, &scaled(10.0, &q));
+            (
+                "rastrigin rotated",
+                Box::new(move |x: &[f64]| {
+                    let z = asymmetric(0.2, oscillate(multiply(&r, &shift(x, &xopt))));
+                    rastrigin(&multiply(&m, &z))
+                }),
+            )
+        }
+        16 => {
+            let m = product(&r, &scaled(0.01, &q));
+            let f0 = (0..12)
+                .map(|k| 0.5_f64.powi(k) * (PI * 3_f64.powi(k)).cos())
+                .sum::<f64>();
+            (
+                "weierstrass",
+                Box::new(move |x: &[f64]| {
+                    let z = multiply(&m, &oscillate(multiply(&r, &shift(x, &xopt))));
+                    let sum = z
+                        .iter()
+                        .map(|z| {
+                            (0..12)
+                                .map(|k| {
+                                    0.5_f64.powi(k) * (2.0 * PI * 3_f64.powi(k) * (z + 0.5)).cos()
+                                })
+                                .sum::<f64>()
+                        })
+                        .sum::<f64>();
+                    10.0 * (sum / d as f64 - f0).powi(3) + 10.0 / d as f64 * penalty(x)
+                }),
+            )
+        }
+        17 | 18 => {
+            let m = scaled(if f == 17 { 10.0 } else { 1000.0 }, &q);
+            (
+                if f == 17 {
+                    "schaffer f7"
+                } else {
+                    "schaffer f7 ill-conditioned"
+                },
+                Box::new(move |x: &[f64]| {
+                    let z = multiply(&m, &asymmetric(0.5, multiply(&r, &shift(x, &xopt))));
+                    let sum = z
+                        .windows(2)
+                        .map(|z| {
+                            let s = z[0] * z[0] + z[1] * z[1];
+                            s.powf(0.25) * (1.0 + (50.0 * s.powf(0.1)).sin().powi(2))
+                        })
+                        .sum::<f64>();
+                    (sum / (d as f64 - 1.0).max(1.0)).powi(2) + 10.0 * penalty(x)
+                }),
+            )
+        }
+        19 => {
+            let factor = ((d as f64).sqrt() / 8.0).max(1.0);
+            (
+                "griewank-rosenbrock",
+                Box::new(move |x: &[f64]| {
+                    let z = multiply(&q, x)
+                        .into_iter()
+                        .map(|z| factor * z + 0.5)
+                        .collect::<Vec<_>>();
+                    let sum = z
+                        .windows(2)
+                        .map(|z| {
+                            let s = 100.0 * (z[0] * z[0] - z[1]).powi(2) + (z[0] - 1.0).powi(2);
+                            s / 4000.0 - s.cos()
+                        })
+                        .sum::<f64>();
+                    10.0 * sum / (d as f64 - 1.0).max(1.0) + 10.0
+                }),
+            )
+        }
+        20 => {
+            let xopt = optimum.clone();
+            (
+                "schwefel",
+                Box::new(move |x: &[f64]| {
+                    let hat = x
+                        .iter()
+                        .zip(&xopt)
+                        .map(|(x, o)| 2.0 * o.signum() * x)
+                        .collect::<Vec<_>>();
+                    let z = (0..d)
+                        .map(|i| {
+                            let z = if i == 0 {
+                                hat[0]
+                            } else {
+                                hat[i] + 0.25 * (hat[i - 1] - 2.0 * xopt[i - 1].abs())
+                            };
+                            let o = 2.0 * xopt[i].abs();
+                            100.0 * (10_f64.powf(0.5 * exponent(i, d)) * (z - o) + o)
+                        })
+                        .collect::<Vec<_>>();
+                    let penalty = z
+                        .iter()
+                        .map(|z| (z.abs() - 500.0).max(0.0).powi(2))
+                        .sum::<f64>();
+                    let sum = z.iter().map(|z| z * z.abs().sqrt().sin()).sum::<f64>();
+                    0.01 * (penalty + 418.9828872724339 - sum / d as f64)
+                }),
+            )
+        }
+        21 | 22 => {
+            let gallagher = Gallagher::new(if f == 21 { 101 } else { 21 }, rseed, d);
+            (
+                if f == 21 {
+                    "gallagher 101 peaks"
+                } else {
+                    "gallagher 21 peaks"
+                },
+                Box::new(move |x: &[f64]| gallagher.evaluate(x)),
+            )
+        }
+        23 => {
+            let m = product(&q, &scaled(100.0, &r));
+            (
+                "katsuura",
+                Box::new(move |x: &[f64]| {
+                    let z = multiply(&m, &shift(x, &xopt));
+                    let n = d as f64;
+                    let product = z
+                        .iter()
+                        .enumerate()
+                        .map(|(i, z)| {
+                            let sum = (1..=32)
+                                .map(|j| {
+                                    let p = 2_f64.powi(j);
+                                    (p * z - (p * z + 0.5).floor()).abs() / p
+                                })
+                                .sum::<f64>();
+                            1.0 + (i + 1) as f64 * sum
+                        })
+                        .product::<f64>();
+                    10.0 / (n * n) * (product.powf(10.0 / n.powf(1.2)) - 1.0) + penalty(x)
+                }),
+            )
+        }
+        24 => {
+            let (mu0, s) = (2.5, 1.0 - 0.5 / ((d as f64 + 20.0).sqrt() - 4.1));
+            let mu1 = -((mu0 * mu0 - 1.0) / s).sqrt();
+            let signs = optimum.iter().map(|o| o.signum()).collect::<Vec<_>>();
+            let m = product(&q, &scaled(100.0, &r));
+            (
+                "lunacek bi-rastrigin",
+                Box::new(move |x: &[f64]| {
+                    let hat = x
+                        .iter()
+                        .zip(&signs)
+                        .map(|(x, s)| 2.0 * s * x)
+                        .collect::<Vec<_>>();
+                    let z = multiply(&m, &hat.iter().map(|x| x - mu0).collect::<Vec<_>>());
+                    let first = hat.iter().map(|x| (x - mu0).powi(2)).sum::<f64>();
+                    let second = hat.iter().map(|x| (x - mu1).powi(2)).sum::<f64>();
+                    let cosines = z.iter().map(|z| (2.0 * PI * z).cos()).sum::<f64>();
+                    first.min(d as f64 + s * second)
+                        + 10.0 * (d as f64 - cosines)
+                        + 1e4 * penalty(x)
+                }),
+            )
+        }
+        f => panic!("Unknown bbob function: {f}"),
+    };
+
+    Function {
+        name: format!("bbob f{f} {name}"),
+        func: Box::new(move |coords: &Vector<SIZE>| {
+            func(&coords.iter().copied().collect::<Vec<_>>()) + fopt
+        }),
+        batch: None,
+        truth: None,
+        minima: fopt,
+        optima: vec![Vector::from(std::array::from_fn(|d| optimum[d]))],
+        bounds: vec![Bound::from((-5.0, 5.0)); SIZE],
+        dimension: None,
+        constraints: Vec::new(),
+    }
+}
+
+/// Location of the optimum of function `f`, which is `xopt` unless the
+/// function moves it or derives it differently.
+fn optimum(f: usize, rseed: i64, xopt: &[f64], q: &[Vec<f64>]) -> Vec<f64> {
+    let d = xopt.len();
+    match f {
+        4 => xopt
+            .iter()
+            .enumerate()
+            .map(|(i, x)| if i % 2 == 0 { x.abs() } else { *x })
+            .collect(),
+        5 => xopt
+            .iter()
+            .map(|x| if *x < 0.0 { -5.0 } else { 5.0 })
+            .collect(),
+        8 => xopt.iter().map(|x| 0.75 * x).collect(),
+        // `factor Q x + 0.5` is one everywhere at the optimum
+        9 | 19 => {
+            let factor = ((d as f64).sqrt() / 8.0).max(1.0);
+            (0..d)
+                .map(|i| (0..d).map(|k| q[k][i] * 0.5 / factor).sum())
+                .collect()
+        }
+        20 => unif(d, rseed)
+            .into_iter()
+            .map(|u| 0.5 * 4.2096874637 * if u < 0.5 { -1.0 } else { 1.0 })
+            .collect(),
+        // the highest peak, whose location is unrotated
+        21 | 22 => {
+            let (b, c) = if f == 21 { (10.0, 5.0) } else { (9.8, 4.9) };
+            unif(d, rseed)
+                .into_iter()
+                .map(|u| 0.8 * (b * u - c))
+                .collect()
+        }
+        24 => gauss(d, rseed)
+            .into_iter()
+            .map(|g| if g < 0.0 { -1.25 } else { 1.25 })
+            .collect(),
+        _ => xopt.to_vec(),
+    }
+}
+
+/// Uniform numbers in `(0, 1]` from the bbob2009 generator.
+fn unif(n: usize, seed: i64) -> Vec<f64> {
+    let next = |seed: i64| {
+        let tmp = seed / 127773;
+        let seed = 16807 * (seed - tmp * 127773) - 2836 * tmp;
+        if seed < 0 {
+            seed + 2147483647
+        } else {
+            seed
+        }
+    };
+
+    let mut seed = seed.abs().max(1);
+    let mut table = [0; 32];
+    for i in (0..40).rev() {
+        seed = next(seed);
+        if i < 32 {
+            table[i] = seed;
+        }
+    }
+    let mut current = table[0];
+    (0..n)
+        .map(|_| {
+            seed = next(seed);
+            let i = (current / 67108865) as usize;
+            current = table[i];
+            table[i] = seed;
+            let r = current as f64 / 2.147483647e9;
+            if r == 0.0 {
+                1e-99
+            } else {
+                r
+            }
+        })
+        .collect()
+}
+
+/// Standard normal numbers from the bbob2009 generator.
+fn gauss(n: usize, seed: i64) -> Vec<f64> {
+    let u = unif(2 * n, seed);
+    (0..n)
+        .map(|i| {
+            let g = (-2.0 * u[i].ln()).sqrt() * (2.0 * PI * u[n + i]).cos();
+            if g == 0.0 {
+                1e-99
+            } else {
+                g
+            }
+        })
+        .collect()
+}
+
+/// Optimum location, uniform in `[-4, 4]` on a grid of `1e-4`.
+fn xopt(seed: i64, d: usize) -> Vec<f64> {
+    unif(d, seed)
+        .into_iter()
+        .map(|u| {
+            let x = 8.0 * (1e4 * u).floor() / 1e4 - 4.0;
+            if x == 0.0 {
+                -1e-5
+            } else {
+                x
+            }
+        })
+        .collect()
+}
+
+/// Optimal value, a Cauchy distributed number rounded to two decimals and
+/// clipped to `[-1000, 1000]`.
+fn fopt(seed: i64) -> f64 {
+    let (g1, g2) = (gauss(1, seed)[0], gauss(1, seed + 1)[0]);
+    ((100.0 * 100.0 * g1 / g2 + 0.5).floor() / 100.0).clamp(-1000.0, 1000.0)
+}
+
+/// Orthogonal matrix from Gram-Schmidt orthonormalisation of the columns of
+/// a matrix of bbob2009 normal numbers.
+fn rotation(seed: i64, d: usize) -> Vec<Vec<f64>> {
+    let g = gauss(d * d, seed);
+    let mut b = (0..d)
+        .map(|i| (0..d).map(|j| g[j * d + i]).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    for i in 0..d {
+        for j in 0..i {
+            let dot = (0..d).map(|k| b[k][i] * b[k][j]).sum::<f64>();
+            for row in b.iter_mut() {
+                row[i] -= dot * row[j];
+            }
+        }
+        let norm = (0..d).map(|k| b[k][i] * b[k][i]).sum::<f64>().sqrt();
+        for row in b.iter_mut() {
+            row[i] /= norm;
+        }
+    }
+
+    b
+}
+
+/// `(i - 1) / (D - 1)` for the one-based dimension `i`.
+fn exponent(i: usize, d: usize) -> f64 {
+    i as f64 / (d as f64 - 1.0).max(1.0)
+}
+
+/// `Λ^α M`, scaling row `i` of `m` by `α^(0.5 (i - 1) / (D - 1))`.
+fn scaled(alpha: f64, m: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let d = m.len();
+    m.iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let s = alpha.powf(0.5 * exponent(i, d));
+            row.iter().map(|m| s * m).collect()
+        })
+        .collect()
+}
+
+fn product(a: &[Vec<f64>], b: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    a.iter()
+        .map(|row| {
+            (0..b.len())
+                .map(|j| row.iter().zip(b).map(|(a, b)| a * b[j]).sum())
+                .collect()
+        })
+        .collect()
+}
+
+fn multiply(m: &[Vec<f64>], x: &[f64]) -> Vec<f64> {
+    m.iter()
+        .map(|row| row.iter().zip(x).map(|(m, x)| m * x).sum())
+        .collect()
+}
+
+fn shift(x: &[f64], xopt: &[f64]) -> Vec<f64> {
+    x.iter().zip(xopt).map(|(x, o)| x - o).collect()
+}
+
+/// `Λ^α z`
+fn condition(alpha: f64, z: Vec<f64>) -> Vec<f64> {
+    let d = z.len();
+    z.into_iter()
+        .enumerate()
+        .map(|(i, z)| alpha.powf(0.5 * exponent(i, d)) * z)
+        .collect()
+}
+
+/// The oscillation transformation `T_osz`.
+fn tosz(x: f64) -> f64 {
+    if x == 0.0 {
+        return 0.0;
+    }
+    let hat = x.abs().ln();
+    let (c1, c2) = if x > 0.0 { (10.0, 7.9) } else { (5.5, 3.1) };
+
+    x.signum() * (hat + 0.049 * ((c1 * hat).sin() + (c2 * hat).sin())).exp()
+}
+
+fn oscillate(z: Vec<f64>) -> Vec<f64> {
+    z.into_iter().map(tosz).collect()
+}
+
+/// The asymmetric transformation `T_asy^β`.
+fn asymmetric(beta: f64, z: Vec<f64>) -> Vec<f64> {
+    let d = z.len();
+    z.into_iter()
+        .enumerate()
+        .map(|(i, z)| {
+            if z > 0.0 {
+                z.powf(1.0 + beta * exponent(i, d) * z.sqrt())
+            } else {
+                z
+            }
+        })
+        .collect()
+}
+
+/// `f_pen`, the squared distance outside of `[-5, 5]`.
+fn penalty(x: &[f64]) -> f64 {
+    x.iter().map(|x| (x.abs() - 5.0).max(0.0).powi(2)).sum()
+}
+
+fn sphere(z: &[f64]) -> f64 {
+    z.iter().map(|z| z * z).sum()
+}
+
+fn ellipsoid(z: &[f64]) -> f64 {
+    let d = z.len();
+    z.iter()
+        .enumerate()
+        .map(|(i, z)| 10_f64.powf(6.0 * exponent(i, d)) * z * z)
+        .sum()
+}
+
+fn rastrigin(z: &[f64]) -> f64 {
+    10.0 * (z.len() as f64 - z.iter().map(|z| (2.0 * PI * z).cos()).sum::<f64>()) + sphere(z)
+}
+
+fn rosenbrock(z: &[f64]) -> f64 {
+    z.windows(2)
+        .map(|z| 100.0 * (z[0] * z[0] - z[1]).powi(2) + (z[0] - 1.0).powi(2))
+        .sum()
+}
+
+/// Gallagher's Gaussian peaks, the peak positions, heights and conditioning
+/// following the COCO legacy code.
+struct Gallagher {
+    rotation: Vec<Vec<f64>>,
+    /// location of every peak in the rotated space
+    peaks: Vec<Vec<f64>>,
+    heights: Vec<f64>,
+    /// diagonal conditioning of every peak
+    scales: Vec<Vec<f64>>,
+}
+
+impl Gallagher {
+    fn new(n: usize, rseed: i64, d: usize) -> Self {
+        let (condition, b, c) = if n == 101 {
+            (1000_f64.sqrt(), 10.0, 5.0)
+        } else {
+            (1000.0, 9.8, 4.9)
+        };
+        let rotation = rotation(rseed, d);
+        let permutation = |u: Vec<f64>| {
+            let mut indices = (0..u.len()).collect::<Vec<_>>();
+            indices.sort_by(|i, j| u[*i].total_cmp(&u[*j]));
+            indices
+        };
+
+        let order = permutation(unif(n - 1, rseed));
+        let conditions = std::iter::once(condition)
+            .chain(
+                order
+                    .iter()
+                    .map(|&k| 1000_f64.powf(k as f64 / (n as f64 - 2.0))),
+            )
+            .collect::<Vec<_>>();
+        let heights = std::iter::once(10.0)
+            .chain((1..n).map(|i| 1.1 + 8.0 * (i - 1) as f64 / (n as f64 - 2.0)))
+            .collect();
+        let scales = (0..n)
+            .map(|i| {
+                permutation(unif(d, rseed + 1000 * i as i64))
+                    .into_iter()
+                    .map(|k| conditions[i].powf(exponent(k, d) - 0.5))
+                    .collect()
+            })
+            .collect();
+        let u = unif(d * n, rseed);
+        let peaks = (0..n)
+            .map(|j| {
+                let y = (0..d).map(|k| b * u[j * d + k] - c).collect::<Vec<_>>();
+                let peak = multiply(&rotation, &y);
+                if j == 0 {
+                    peak.into_iter().map(|y| 0.8 * y).collect()
+                } else {
+                    peak
+                }
+            })
+            .collect();
+
+        Self {
+            rotation,
+            peaks,
+            heights,
+            scales,
+        }
+    }
+
+    fn evaluate(&self, x: &[f64]) -> f64 {
+        let d = x.len() as f64;
+        let z = multiply(&self.rotation, x);
+        let best = self
+            .peaks
+            .iter()
+            .zip(&self.heights)
+            .zip(&self.scales)
+            .map(|((peak, height), scales)| {
+                let distance = z
+                    .iter()
+                    .zip(peak)
+                    .zip(scales)
+                    .map(|((z, y), s)| s * (z - y) * (z - y))
+                    .sum::<f64>();
+                height * (-0.5 / d * distance).exp()
+            })
+            .fold(f64::MIN, f64::max);
+
+        tosz(10.0 - best).powi(2) + penalty(x)
+    }
+}
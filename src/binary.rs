@@ -0,0 +1,118 @@
+use crate::function::{Constraint, Function};
+use mikes_pso::{bounds::Bound, vector::Vector};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Number of bits in every block of the deceptive trap.
+const TRAP: usize = 5;
+
+/// Binary benchmarks over `SIZE` bits, where every coordinate is either zero
+/// or one and the maximisation problems are negated to be minimised: OneMax,
+/// a 0-1 knapsack and the concatenated deceptive trap.
+pub fn functions<const SIZE: usize>() -> Vec<Function<SIZE>> {
+    vec![onemax(), knapsack(), trap()]
+}
+
+/// Number of ones.
+fn onemax<const SIZE: usize>() -> Function<SIZE> {
+    Function {
+        name: "onemax".to_string(),
+        func: Box::new(|bits: &Vector<SIZE>| -bits.iter().sum::<f64>()),
+        batch: None,
+        truth: None,
+        minima: -(SIZE as f64),
+        optima: vec![Vector::from([1.0; SIZE])],
+        bounds: vec![Bound::from((0.0, 1.0)); SIZE],
+        dimension: None,
+        constraints: Vec::new(),
+    }
+}
+
+/// Total value of the chosen items whose total weight must not exceed half
+/// of the weight of every item, with integer weights and values between 1
+/// and 100 drawn from a fixed seed. Overweight solutions are also penalised
+/// by the total value per unit of excess weight, which makes every one of
+/// them worse than any feasible solution for unconstrained optimisers.
+fn knapsack<const SIZE: usize>() -> Function<SIZE> {
+    let mut rng = StdRng::seed_from_u64(0);
+    let items: [(usize, f64); SIZE] =
+        std::array::from_fn(|_| (rng.gen_range(1..=100), rng.gen_range(1..=100) as f64));
+    let capacity = items.iter().map(|(weight, _)| weight).sum::<usize>() / 2;
+    let penalty = items.iter().map(|(_, value)| value).sum::<f64>();
+    let weight = move |bits: &Vector<SIZE>| {
+        (0..SIZE)
+            .filter(|&i| bits[i] == 1.0)
+            .map(|i| items[i].0)
+            .sum::<usize>() as f64
+    };
+
+    // dynamic programming over the capacity for the best value and the
+    // items which reach it
+    let mut best = vec![vec![0.0_f64; capacity + 1]; SIZE + 1];
+    for (i, &(w, value)) in items.iter().enumerate() {
+        for c in 0..=capacity {
+            best[i + 1][c] = best[i][c];
+            if w <= c {
+                best[i + 1][c] = best[i + 1][c].max(best[i][c - w] + value);
+            }
+        }
+    }
+    let mut optimum = [0.0; SIZE];
+    let mut c = capacity;
+    for i in (0..SIZE).rev() {
+        if best[i + 1][c] != best[i][c] {
+            optimum[i] = 1.0;
+            c -= items[i].0;
+        }
+    }
+
+    Function {
+        name: "knapsack".to_string(),
+        func: Box::new(move |bits: &Vector<SIZE>| {
+            let value = (0..SIZE)
+                .filter(|&i| bits[i] == 1.0)
+                .map(|i| items[i].1)
+                .sum::<f64>();
+            -value + penalty * (weight(bits) - capacity as f64).max(0.0)
+        }),
+        batch: None,
+        truth: None,
+        minima: -best[SIZE][capacity],
+        optima: vec![Vector::from(optimum)],
+        bounds: vec![Bound::from((0.0, 1.0)); SIZE],
+        dimension: None,
+        constraints: vec![Constraint::Inequality(Box::new(
+            move |bits: &Vector<SIZE>| weight(bits) - capacity as f64,
+        ))],
+    }
+}
+
+/// Sum over consecutive blocks of `TRAP` bits of a trap whose slope leads
+/// away from its optimum of all ones, `k` for `k` ones and `k - 1 - u` for
+/// `u < k` ones, with a shorter last block when `SIZE` is not a multiple.
+fn trap<const SIZE: usize>() -> Function<SIZE> {
+    Function {
+        name: "deceptive trap".to_string(),
+        func: Box::new(|bits: &Vector<SIZE>| {
+            let bits = bits.iter().copied().collect::<Vec<_>>();
+            -bits
+                .chunks(TRAP)
+                .map(|block| {
+                    let k = block.len() as f64;
+                    let ones = block.iter().sum::<f64>();
+                    if ones == k {
+                        k
+                    } else {
+                        k - 1.0 - ones
+                    }
+                })
+                .sum::<f64>()
+        }),
+        batch: None,
+        truth: None,
+        minima: -(SIZE as f64),
+        optima: vec![Vector::from([1.0; SIZE])],
+        bounds: vec![Bound::from((0.0, 1.0)); SIZE],
+        dimension: None,
+        constraints: Vec::new(),
+    }
+}
@@ -0,0 +1,493 @@
+use crate::baseline::gaussian;
+use crate::function::Function;
+use mikes_pso::{bounds::Bound, vector::Vector};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::f64::consts::{E, PI};
+
+/// Search range of every function in the suite.
+const BOUND: f64 = 100.0;
+
+/// Range the optima are shifted within, kept away from the bounds as in the
+/// CEC suites.
+const SHIFT: f64 = 80.0;
+
+/// A basic function of the suite, evaluated on already shifted and rotated
+/// coordinates with its optimum at the origin.
+#[derive(Clone, Copy)]
+struct Base {
+    name: &'static str,
+    func: fn(&[f64]) -> f64,
+    /// factor mapping `[-100, 100]` onto the natural range of the function
+    scale: f64,
+}
+
+const BENT_CIGAR: Base = Base {
+    name: "bent cigar",
+    func: bent_cigar,
+    scale: 1.0,
+};
+const ZAKHAROV: Base = Base {
+    name: "zakharov",
+    func: zakharov,
+    scale: 1.0,
+};
+const ROSENBROCK: Base = Base {
+    name: "rosenbrock",
+    func: rosenbrock,
+    scale: 2.048 / 100.0,
+};
+const RASTRIGIN: Base = Base {
+    name: "rastrigin",
+    func: rastrigin,
+    scale: 5.12 / 100.0,
+};
+const SCHAFFER: Base = Base {
+    name: "expanded schaffer f6",
+    func: expanded_schaffer,
+    scale: 1.0,
+};
+const LEVY: Base = Base {
+    name: "levy",
+    func: levy,
+    scale: 1.0,
+};
+const SCHWEFEL: Base = Base {
+    name: "schwefel",
+    func: schwefel,
+    scale: 1000.0 / 100.0,
+};
+const ELLIPTIC: Base = Base {
+    name: "high conditioned elliptic",
+    func: elliptic,
+    scale: 1.0,
+};
+const DISCUS: Base = Base {
+    name: "discus",
+    func: discus,
+    scale: 1.0,
+};
+const ACKLEY: Base = Base {
+    name: "ackley",
+    func: ackley,
+    scale: 1.0,
+};
+const WEIERSTRASS: Base = Base {
+    name: "weierstrass",
+    func: weierstrass,
+    scale: 0.5 / 100.0,
+};
+const GRIEWANK: Base = Base {
+    name: "griewank",
+    func: griewank,
+    scale: 600.0 / 100.0,
+};
+const HAPPYCAT: Base = Base {
+    name: "happycat",
+    func: happycat,
+    scale: 5.0 / 100.0,
+};
+const HGBAT: Base = Base {
+    name: "hgbat",
+    func: hgbat,
+    scale: 5.0 / 100.0,
+};
+
+/// Shifted and rotated functions, hybrid functions and composition functions
+/// following the construction of the CEC 2017 and CEC 2022 suites. Every
+/// shift vector, rotation matrix and permutation is generated from `seed` so
+/// that the same seed always gives the same suite, and function `i` has its
+/// minimum of `100 i` at its shift vector.
+pub fn functions<const SIZE: usize>(seed: u64) -> Vec<Function<SIZE>> {
+    let simple = [
+        BENT_CIGAR,
+        ZAKHAROV,
+        ROSENBROCK,
+        RASTRIGIN,
+        SCHAFFER,
+        LEVY,
+        SCHWEFEL,
+        ELLIPTIC,
+        DISCUS,
+        ACKLEY,
+        WEIERSTRASS,
+        GRIEWANK,
+        HAPPYCAT,
+        HGBAT,
+    ];
+    let hybrid: [&[(Base, f64)]; 5] = [
+        &[(ZAKHAROV, 0.2), (ROSENBROCK, 0.4), (RASTRIGIN, 0.4)],
+        &[(ELLIPTIC, 0.3), (SCHWEFEL, 0.3), (BENT_CIGAR, 0.4)],
+        &[
+            (BENT_CIGAR, 0.2),
+            (HGBAT, 0.2),
+            (RASTRIGIN, 0.3),
+            (ROSENBROCK, 0.3),
+        ],
+        &[
+            (SCHAFFER, 0.2),
+            (HGBAT, 0.2),
+            (ROSENBROCK, 0.3),
+            (SCHWEFEL, 0.3),
+        ],
+        &[
+            (GRIEWANK, 0.2),
+            (WEIERSTRASS, 0.2),
+            (ROSENBROCK, 0.2),
+            (SCHAFFER, 0.2),
+            (HAPPYCAT, 0.2),
+        ],
+    ];
+    // (function, sigma, lambda) of every component
+    let composition: [&[(Base, f64, f64)]; 5] = [
+        &[
+            (ROSENBROCK, 10.0, 1.0),
+            (ELLIPTIC, 20.0, 1e-6),
+            (RASTRIGIN, 30.0, 1.0),
+        ],
+        &[
+            (RASTRIGIN, 10.0, 1.0),
+            (GRIEWANK, 20.0, 10.0),
+            (SCHWEFEL, 30.0, 1.0),
+        ],
+        &[
+            (ROSENBROCK, 10.0, 1.0),
+            (ACKLEY, 20.0, 10.0),
+            (SCHWEFEL, 30.0, 1.0),
+            (RASTRIGIN, 40.0, 1.0),
+        ],
+        &[
+            (ACKLEY, 10.0, 10.0),
+            (ELLIPTIC, 20.0, 1e-6),
+            (GRIEWANK, 30.0, 10.0),
+            (RASTRIGIN, 40.0, 1.0),
+        ],
+        &[
+            (RASTRIGIN, 10.0, 10.0),
+            (HAPPYCAT, 20.0, 1.0),
+            (ACKLEY, 30.0, 10.0),
+            (DISCUS, 40.0, 1e-6),
+            (ROSENBROCK, 50.0, 1.0),
+        ],
+    ];
+
+    let mut functions = Vec::with_capacity(simple.len() + hybrid.len() + composition.len());
+    for base in simple {
+        let i = functions.len() + 1;
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(i as u64));
+        let component = Component::new(base, &mut rng, SIZE);
+        let optimum = component.shift.clone();
+        functions.push(cec(
+            i,
+            format!("shifted rotated {}", base.name),
+            &optimum,
+            move |x| component.evaluate(x),
+        ));
+    }
+
+    for groups in hybrid {
+        let i = functions.len() + 1;
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(i as u64));
+        let shift = shift(&mut rng, SIZE);
+        let rotation = rotation(&mut rng, SIZE);
+        let mut permutation = (0..SIZE).collect::<Vec<_>>();
+        permutation.shuffle(&mut rng);
+        let groups = groups.to_vec();
+        let name = groups
+            .iter()
+            .map(|(base, _)| base.name)
+            .collect::<Vec<_>>()
+            .join(" + ");
+        let optimum = shift.clone();
+
+        functions.push(cec(i, format!("hybrid ({name})"), &optimum, move |x| {
+            let z = rotate(&rotation, &shift, x);
+            let z = permutation.iter().map(|&d| z[d]).collect::<Vec<_>>();
+            let mut start = 0;
+            groups
+                .iter()
+                .enumerate()
+                .map(|(g, (base, proportion))| {
+                    let end = if g + 1 == groups.len() {
+                        z.len()
+                    } else {
+                        (start + (proportion * z.len() as f64).ceil() as usize).min(z.len())
+                    };
+                    let segment = z[start..end]
+                        .iter()
+                        .map(|z| z * base.scale)
+                        .collect::<Vec<_>>();
+                    start = end;
+                    if segment.is_empty() {
+                        0.0
+                    } else {
+                        (base.func)(&segment)
+                    }
+                })
+                .sum()
+        }));
+    }
+
+    for components in composition {
+        let i = functions.len() + 1;
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(i as u64));
+        let components = components
+            .iter()
+            .map(|&(base, sigma, lambda)| (Component::new(base, &mut rng, SIZE), sigma, lambda))
+            .collect::<Vec<_>>();
+        let name = components
+            .iter()
+            .map(|(component, _, _)| component.base.name)
+            .collect::<Vec<_>>()
+            .join(" + ");
+
+        // the first component has the lowest bias so the optimum is its own
+        let optimum = components[0].0.shift.clone();
+        functions.push(cec(
+            i,
+            format!("composition ({name})"),
+            &optimum,
+            move |x| {
+                let mut values = Vec::with_capacity(components.len());
+                let mut weights = Vec::with_capacity(components.len());
+                for (c, (component, sigma, lambda)) in components.iter().enumerate() {
+                    let distance = x
+                        .iter()
+                        .zip(&component.shift)
+                        .map(|(x, o)| (x - o) * (x - o))
+                        .sum::<f64>();
+                    let value = lambda * component.evaluate(x) + 100.0 * c as f64;
+                    if distance == 0.0 {
+                        return value;
+                    }
+                    values.push(value);
+                    weights.push(
+                        (-distance / (2.0 * x.len() as f64 * sigma * sigma)).exp()
+                            / distance.sqrt(),
+                    );
+                }
+                // every weight underflows far away from all optima
+                if weights.iter().all(|w| *w == 0.0) {
+                    weights.fill(1.0);
+                }
+
+                values.iter().zip(&weights).map(|(v, w)| v * w).sum::<f64>()
+                    / weights.iter().sum::<f64>()
+            },
+        ));
+    }
+
+    functions
+}
+
+/// Function `i` of the suite, offset so that its minimum is `100 i` at
+/// `optimum`.
+fn cec<const SIZE: usize>(
+    i: usize,
+    name: String,
+    optimum: &[f64],
+    func: impl Fn(&[f64]) -> f64 + Send + 'static,
+) -> Function<SIZE> {
+    let bias = 100.0 * i as f64;
+    Function {
+        name: format!("cec f{i} {name}"),
+        func: Box::new(move |coords: &Vector<SIZE>| {
+            func(&coords.iter().copied().collect::<Vec<_>>()) + bias
+        }),
+        batch: None,
+        truth: None,
+        minima: bias,
+        optima: vec![Vector::from(std::array::from_fn(|d| optimum[d]))],
+        bounds: vec![Bound::from((-BOUND, BOUND)); SIZE],
+        dimension: None,
+        constraints: Vec::new(),
+    }
+}
+
+/// A basic function with its own shift vector and rotation matrix.
+struct Component {
+    base: Base,
+    shift: Vec<f64>,
+    rotation: Vec<Vec<f64>>,
+}
+
+impl Component {
+    fn new(base: Base, rng: &mut impl Rng, size: usize) -> Self {
+        Self {
+            base,
+            shift: shift(rng, size),
+            rotation: rotation(rng, size),
+        }
+    }
+
+    /// `f(M (s (x - o)))` where `s` is the scale of the basic function.
+    fn evaluate(&self, x: &[f64]) -> f64 {
+        let z = rotate(&self.rotation, &self.shift, x)
+            .into_iter()
+            .map(|z| z * self.base.scale)
+            .collect::<Vec<_>>();
+
+        (self.base.func)(&z)
+    }
+}
+
+/// Shift vector drawn uniformly from `[-80, 80]` in every dimension.
+pub fn shift(rng: &mut impl Rng, size: usize) -> Vec<f64> {
+    (0..size).map(|_| rng.gen_range(-SHIFT..SHIFT)).collect()
+}
+
+/// Random orthogonal matrix from Gram-Schmidt orthonormalisation of a matrix
+/// of standard normal samples.
+pub fn rotation(rng: &mut impl Rng, size: usize) -> Vec<Vec<f64>> {
+    let mut rows: Vec<Vec<f64>> = Vec::with_capacity(size);
+    while rows.len() < size {
+        let mut row = (0..size).map(|_| gaussian(rng)).collect::<Vec<_>>();
+        for other in &rows {
+            let dot = row.iter().zip(other).map(|(a, b)| a * b).sum::<f64>();
+            row.iter_mut().zip(other).for_each(|(a, b)| *a -= dot * b);
+        }
+        let norm = row.iter().map(|a| a * a).sum::<f64>().sqrt();
+        // redraw rows which are (numerically) dependent on the previous ones
+        if norm > 1e-8 {
+            row.iter_mut().for_each(|a| *a /= norm);
+            rows.push(row);
+        }
+    }
+
+    rows
+}
+
+/// `M (x - o)`
+pub fn rotate(rotation: &[Vec<f64>], shift: &[f64], x: &[f64]) -> Vec<f64> {
+    let shifted = x.iter().zip(shift).map(|(x, o)| x - o).collect::<Vec<_>>();
+    rotation
+        .iter()
+        .map(|row| row.iter().zip(&shifted).map(|(m, x)| m * x).sum())
+        .collect()
+}
+
+fn bent_cigar(z: &[f64]) -> f64 {
+    z[0] * z[0] + 1e6 * z[1..].iter().map(|z| z * z).sum::<f64>()
+}
+
+fn zakharov(z: &[f64]) -> f64 {
+    let linear = z
+        .iter()
+        .enumerate()
+        .map(|(i, z)| 0.5 * (i + 1) as f64 * z)
+        .sum::<f64>();
+
+    z.iter().map(|z| z * z).sum::<f64>() + linear.powi(2) + linear.powi(4)
+}
+
+/// Rosenbrock moved so that its optimum lies at the origin.
+fn rosenbrock(z: &[f64]) -> f64 {
+    z.windows(2)
+        .map(|z| {
+            let (x, y) = (z[0] + 1.0, z[1] + 1.0);
+            100.0 * (x * x - y).powi(2) + (x - 1.0).powi(2)
+        })
+        .sum()
+}
+
+fn rastrigin(z: &[f64]) -> f64 {
+    z.iter()
+        .map(|z| z * z - 10.0 * (2.0 * PI * z).cos() + 10.0)
+        .sum()
+}
+
+fn expanded_schaffer(z: &[f64]) -> f64 {
+    (0..z.len())
+        .map(|i| {
+            let r = z[i] * z[i] + z[(i + 1) % z.len()] * z[(i + 1) % z.len()];
+            0.5 + (r.sqrt().sin().powi(2) - 0.5) / (1.0 + 0.001 * r).powi(2)
+        })
+        .sum()
+}
+
+/// Levy moved so that its optimum lies at the origin.
+fn levy(z: &[f64]) -> f64 {
+    let w = z.iter().map(|z| 1.0 + z / 4.0).collect::<Vec<_>>();
+    let last = w[w.len() - 1];
+
+    (PI * w[0]).sin().powi(2)
+        + w[..w.len() - 1]
+            .iter()
+            .map(|w| (w - 1.0).powi(2) * (1.0 + 10.0 * (PI * w + 1.0).sin().powi(2)))
+            .sum::<f64>()
+        + (last - 1.0).powi(2) * (1.0 + (2.0 * PI * last).sin().powi(2))
+}
+
+/// Modified Schwefel moved so that its optimum lies at the origin.
+fn schwefel(z: &[f64]) -> f64 {
+    let d = z.len() as f64;
+    418.9828872724338 * d
+        - z.iter()
+            .map(|z| {
+                let z = z + 420.9687462275036;
+                if z.abs() <= 500.0 {
+                    z * z.abs().sqrt().sin()
+                } else {
+                    let m = 500.0 - z.abs() % 500.0;
+                    z.signum() * m * m.sqrt().sin() - (z.abs() - 500.0).powi(2) / (10000.0 * d)
+                }
+            })
+            .sum::<f64>()
+}
+
+fn elliptic(z: &[f64]) -> f64 {
+    let d = (z.len() as f64 - 1.0).max(1.0);
+    z.iter()
+        .enumerate()
+        .map(|(i, z)| 1e6_f64.powf(i as f64 / d) * z * z)
+        .sum()
+}
+
+fn discus(z: &[f64]) -> f64 {
+    1e6 * z[0] * z[0] + z[1..].iter().map(|z| z * z).sum::<f64>()
+}
+
+fn ackley(z: &[f64]) -> f64 {
+    let d = z.len() as f64;
+    -20.0 * (-0.2 * (z.iter().map(|z| z * z).sum::<f64>() / d).sqrt()).exp()
+        - (z.iter().map(|z| (2.0 * PI * z).cos()).sum::<f64>() / d).exp()
+        + 20.0
+        + E
+}
+
+fn weierstrass(z: &[f64]) -> f64 {
+    let (a, b, k) = (0.5_f64, 3.0_f64, 20);
+    let term = |x: f64| {
+        (0..=k)
+            .map(|k| a.powi(k) * (2.0 * PI * b.powi(k) * (x + 0.5)).cos())
+            .sum::<f64>()
+    };
+
+    z.iter().map(|z| term(*z)).sum::<f64>() - z.len() as f64 * term(0.0)
+}
+
+fn griewank(z: &[f64]) -> f64 {
+    z.iter().map(|z| z * z).sum::<f64>() / 4000.0
+        - z.iter()
+            .enumerate()
+            .map(|(i, z)| (z / ((i + 1) as f64).sqrt()).cos())
+            .product::<f64>()
+        + 1.0
+}
+
+/// Happycat moved so that its optimum lies at the origin.
+fn happycat(z: &[f64]) -> f64 {
+    let d = z.len() as f64;
+    let squares = z.iter().map(|z| (z - 1.0).powi(2)).sum::<f64>();
+    let sum = z.iter().map(|z| z - 1.0).sum::<f64>();
+
+    (squares - d).abs().powf(0.25) + (0.5 * squares + sum) / d + 0.5
+}
+
+/// Hgbat moved so that its optimum lies at the origin.
+fn hgbat(z: &[f64]) -> f64 {
+    let d = z.len() as f64;
+    let squares = z.iter().map(|z| (z - 1.0).powi(2)).sum::<f64>();
+    let sum = z.iter().map(|z| z - 1.0).sum::<f64>();
+
+    (squares * squares - sum * sum).abs().sqrt() + (0.5 * squares + sum) / d + 0.5
+}
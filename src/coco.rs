@@ -0,0 +1,205 @@
+use crate::metrics::Attempt;
+use std::fs::{self, File};
+use std::io::Write;
+
+/// Header of every run in the `.dat` and `.tdat` files.
+const HEADER: &str = "% f evaluations | g evaluations | best noise-free fitness - Fopt | measured fitness | best measured fitness\n";
+
+/// Writes the runs of one function and dimension in the data format of the
+/// COCO bbob logger, so that `{output}/{algorithm}` can be post-processed by
+/// `cocopp` like any other COCO data folder:
+///
+/// - `bbobexp_f{function}.info` indexes every dimension written so far,
+///   where writing a dimension again replaces its entry just as its data
+///   files are recreated,
+/// - `data_f{function}/bbobexp_f{function}_DIM{dimension}.dat` records every
+///   run each time the best fitness reaches a new target `10^(k/5)` above the
+///   optimum,
+/// - `data_f{function}/bbobexp_f{function}_DIM{dimension}.tdat` records every
+///   run after `{1, 2, 5} 10^k` evaluations.
+///
+/// `runs` holds the instance of every run alongside it.
+pub fn write(
+    output: &str,
+    algorithm: &str,
+    function: usize,
+    dimension: usize,
+    runs: &[(usize, Attempt)],
+) {
+    let folder = format!("{output}/{algorithm}");
+    let data = format!("data_f{function}/bbobexp_f{function}_DIM{dimension}");
+    fs::create_dir_all(format!("{folder}/data_f{function}")).unwrap();
+
+    let mut dat = File::create(format!("{folder}/{data}.dat")).unwrap();
+    let mut tdat = File::create(format!("{folder}/{data}.tdat")).unwrap();
+    for (_, attempt) in runs {
+        dat.write_all(targets(attempt).as_bytes()).unwrap();
+        tdat.write_all(evaluations(attempt).as_bytes()).unwrap();
+    }
+
+    // every entry is a header line, a comment line and the line of runs
+    let path = format!("{folder}/bbobexp_f{function}.info");
+    let previous = fs::read_to_string(&path).unwrap_or_default();
+    let lines = previous.lines().collect::<Vec<_>>();
+    let mut info = File::create(&path).unwrap();
+    for entry in lines.chunks(3) {
+        if !entry[0].contains(&format!(", DIM = {dimension},")) {
+            info.write_all(format!("{}\n", entry.join("\n")).as_bytes())
+                .unwrap();
+        }
+    }
+    let instances = runs
+        .iter()
+        .map(|(instance, attempt)| {
+            format!(
+                "{instance}:{}|{:.1e}",
+                attempt.evaluations,
+                error(
+                    attempt,
+                    attempt.improvements.last().map_or(f64::MAX, |(_, f)| *f)
+                )
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    info.write_all(
+        format!(
+            "funcId = {function}, DIM = {dimension}, Precision = 1.000e-08, algId = '{algorithm}'\n%\n{data}.dat, {instances}\n"
+        )
+        .as_bytes(),
+    )
+    .unwrap();
+}
+
+/// Distance of `fitness` above the optimum.
+fn error(attempt: &Attempt, fitness: f64) -> f64 {
+    fitness - attempt.minima
+}
+
+fn row(attempt: &Attempt, evaluations: usize, fitness: f64) -> String {
+    format!(
+        "{evaluations} 0 {:+.9e} {fitness:+.9e} {fitness:+.9e}\n",
+        error(attempt, fitness)
+    )
+}
+
+/// Rows of the `.dat` file, one whenever a new target is reached and one for
+/// the last evaluation.
+fn targets(attempt: &Attempt) -> String {
+    let mut rows = HEADER.to_string();
+    let mut reached = i64::MAX;
+    for &(evaluations, fitness) in &attempt.improvements {
+        let target = (5.0 * error(attempt, fitness).max(1e-300).log10()).floor() as i64;
+        if target < reached {
+            reached = target;
+            rows.push_str(&row(attempt, evaluations, fitness));
+        }
+    }
+    if let Some(&(evaluations, fitness)) = attempt.improvements.last() {
+        if evaluations < attempt.evaluations {
+            rows.push_str(&row(attempt, attempt.evaluations, fitness));
+        }
+    }
+
+    rows
+}
+
+/// Rows of the `.tdat` file, the best fitness after `{1, 2, 5} 10^k`
+/// evaluations and after the last evaluation.
+fn evaluations(attempt: &Attempt) -> String {
+    let mut rows = HEADER.to_string();
+    let best = |evaluations: usize| {
+        attempt
+            .improvements
+            .iter()
+            .take_while(|(e, _)| *e <= evaluations)
+            .last()
+            .map(|(_, fitness)| *fitness)
+    };
+    let triggers = (0..)
+        .flat_map(|k| [1, 2, 5].map(|m| m * 10_usize.pow(k)))
+        .take_while(|evaluations| *evaluations < attempt.evaluations);
+    for evaluations in triggers.chain([attempt.evaluations]) {
+        if let Some(fitness) = best(evaluations) {
+            rows.push_str(&row(attempt, evaluations, fitness));
+        }
+    }
+
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt() -> Attempt {
+        Attempt {
+            minima: 1.0,
+            improvements: vec![(1, 11.0), (5, 6.0), (7, 5.0), (20, 1.01)],
+            evaluations: 30,
+        }
+    }
+
+    /// Evaluations and error of every row.
+    fn rows(data: &str) -> Vec<(usize, f64)> {
+        data.lines()
+            .skip(1)
+            .map(|line| {
+                let fields = line.split(' ').collect::<Vec<_>>();
+                (fields[0].parse().unwrap(), fields[2].parse().unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn dat() {
+        // an error of 4 is still within the target 10^(3/5) reached by 5
+        assert_eq!(
+            rows(&targets(&attempt())),
+            vec![(1, 10.0), (5, 5.0), (20, 0.01), (30, 0.01)]
+        );
+    }
+
+    #[test]
+    fn tdat() {
+        assert_eq!(
+            rows(&evaluations(&attempt())),
+            vec![
+                (1, 10.0),
+                (2, 10.0),
+                (5, 5.0),
+                (10, 4.0),
+                (20, 0.01),
+                (30, 0.01)
+            ]
+        );
+    }
+
+    #[test]
+    fn info() {
+        let output = std::env::temp_dir().join(format!("coco{}", std::process::id()));
+        let output = output.to_str().unwrap();
+        for dimension in [2, 3, 2] {
+            write(
+                output,
+                "pso",
+                1,
+                dimension,
+                &[(1, attempt()), (2, attempt())],
+            );
+        }
+
+        let info = fs::read_to_string(format!("{output}/pso/bbobexp_f1.info")).unwrap();
+        let lines = info.lines().collect::<Vec<_>>();
+        // the dimension written again replaces its entry
+        assert_eq!(lines.len(), 6);
+        assert!(lines[0].starts_with("funcId = 1, DIM = 3,"));
+        assert!(lines[3].starts_with("funcId = 1, DIM = 2,"));
+        assert_eq!(
+            lines[5],
+            "data_f1/bbobexp_f1_DIM2.dat, 1:30|1.0e-2, 2:30|1.0e-2"
+        );
+        assert!(fs::metadata(format!("{output}/pso/data_f1/bbobexp_f1_DIM3.tdat")).is_ok());
+        fs::remove_dir_all(output).unwrap();
+    }
+}
@@ -0,0 +1,499 @@
+use crate::function::{point, Constraint, Function};
+use mikes_pso::{bounds::Bound, vector::Vector};
+use std::f64::consts::PI;
+
+type Objective = Box<dyn Fn(&[f64]) -> f64 + Send>;
+
+/// Constraint of a problem written over slices, converted by `constrained`.
+enum Rule {
+    /// satisfied when `g(x) <= 0`
+    Inequality(Objective),
+    /// satisfied when `h(x) = 0`
+    Equality(Objective),
+}
+
+/// Evaluates `func` on the coordinates as a slice so that the problems can
+/// be written at their own dimension.
+fn sliced<const SIZE: usize>(func: Objective) -> Box<dyn Fn(&Vector<SIZE>) -> f64 + Send> {
+    Box::new(move |coords: &Vector<SIZE>| func(&coords.iter().copied().collect::<Vec<_>>()))
+}
+
+fn constrained<const SIZE: usize>(
+    name: &str,
+    func: Objective,
+    rules: Vec<Rule>,
+    minima: f64,
+    optimum: &[f64],
+    bounds: Vec<(f64, f64)>,
+) -> Function<SIZE> {
+    Function {
+        name: name.to_string(),
+        func: sliced(func),
+        batch: None,
+        truth: None,
+        minima,
+        optima: vec![point(optimum)],
+        dimension: Some(bounds.len()),
+        bounds: bounds.into_iter().map(Bound::from).collect(),
+        constraints: rules
+            .into_iter()
+            .map(|rule| match rule {
+                Rule::Inequality(g) => Constraint::Inequality(sliced(g)),
+                Rule::Equality(h) => Constraint::Equality(sliced(h)),
+            })
+            .collect(),
+    }
+}
+
+fn inequality(g: impl Fn(&[f64]) -> f64 + Send + 'static) -> Rule {
+    Rule::Inequality(Box::new(g))
+}
+
+fn equality(h: impl Fn(&[f64]) -> f64 + Send + 'static) -> Rule {
+    Rule::Equality(Box::new(h))
+}
+
+/// The constrained problems g01 to g13 of Runarsson and Yao followed by the
+/// pressure vessel, welded beam and tension spring design problems, with
+/// the best known solution of each. Every problem is only defined at its
+/// own dimension, so only those which fit `SIZE` can be run.
+pub fn functions<const SIZE: usize>() -> Vec<Function<SIZE>> {
+    vec![
+        constrained(
+            "g01",
+            Box::new(|x| {
+                5.0 * x[..4].iter().sum::<f64>()
+                    - 5.0 * x[..4].iter().map(|x| x * x).sum::<f64>()
+                    - x[4..].iter().sum::<f64>()
+            }),
+            vec![
+                inequality(|x| 2.0 * x[0] + 2.0 * x[1] + x[9] + x[10] - 10.0),
+                inequality(|x| 2.0 * x[0] + 2.0 * x[2] + x[9] + x[11] - 10.0),
+                inequality(|x| 2.0 * x[1] + 2.0 * x[2] + x[10] + x[11] - 10.0),
+                inequality(|x| -8.0 * x[0] + x[9]),
+                inequality(|x| -8.0 * x[1] + x[10]),
+                inequality(|x| -8.0 * x[2] + x[11]),
+                inequality(|x| -2.0 * x[3] - x[4] + x[9]),
+                inequality(|x| -2.0 * x[5] - x[6] + x[10]),
+                inequality(|x| -2.0 * x[7] - x[8] + x[11]),
+            ],
+            -15.0,
+            &[
+                1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 3.0, 3.0, 3.0, 1.0,
+            ],
+            [vec![(0.0, 1.0); 9], vec![(0.0, 100.0); 3], vec![(0.0, 1.0)]].concat(),
+        ),
+        constrained(
+            "g02",
+            Box::new(|x| {
+                let sum = x.iter().map(|x| x.cos().powi(4)).sum::<f64>();
+                let product = x.iter().map(|x| x.cos().powi(2)).product::<f64>();
+                let norm = x
+                    .iter()
+                    .enumerate()
+                    .map(|(i, x)| (i + 1) as f64 * x * x)
+                    .sum::<f64>()
+                    .sqrt();
+                -(sum - 2.0 * product).abs() / norm
+            }),
+            vec![
+                inequality(|x| 0.75 - x.iter().product::<f64>()),
+                inequality(|x| x.iter().sum::<f64>() - 7.5 * x.len() as f64),
+            ],
+            -0.80361910412559,
+            &[
+                3.16246061572185,
+                3.12833142812967,
+                3.09479212988791,
+                3.06145059523469,
+                3.02792915885555,
+                2.99382606701730,
+                2.95866871765285,
+                2.92184227312450,
+                0.49482511456933,
+                0.48835711005490,
+                0.48231642711865,
+                0.47664475092742,
+                0.47129550835493,
+                0.46623099264167,
+                0.46142004984199,
+                0.45683664767217,
+                0.45245876903267,
+                0.44826762241853,
+                0.44424700958760,
+                0.44038285956317,
+            ],
+            vec![(0.0, 10.0); 20],
+        ),
+        constrained(
+            "g03",
+            Box::new(|x| {
+                let n = x.len() as f64;
+                -n.sqrt().powf(n) * x.iter().product::<f64>()
+            }),
+            vec![equality(|x| x.iter().map(|x| x * x).sum::<f64>() - 1.0)],
+            -1.0,
+            &[1.0 / 10.0_f64.sqrt(); 10],
+            vec![(0.0, 1.0); 10],
+        ),
+        constrained(
+            "g04",
+            Box::new(|x| {
+                5.3578547 * x[2] * x[2] + 0.8356891 * x[0] * x[4] + 37.293239 * x[0] - 40792.141
+            }),
+            vec![
+                inequality(|x| u(x) - 92.0),
+                inequality(|x| -u(x)),
+                inequality(|x| v(x) - 110.0),
+                inequality(|x| -v(x) + 90.0),
+                inequality(|x| w(x) - 25.0),
+                inequality(|x| -w(x) + 20.0),
+            ],
+            -30665.538671783,
+            &[78.0, 33.0, 29.995256025682, 45.0, 36.775812905788],
+            vec![
+                (78.0, 102.0),
+                (33.0, 45.0),
+                (27.0, 45.0),
+                (27.0, 45.0),
+                (27.0, 45.0),
+            ],
+        ),
+        constrained(
+            "g05",
+            Box::new(|x| {
+                3.0 * x[0] + 0.000001 * x[0].powi(3) + 2.0 * x[1] + 0.000002 / 3.0 * x[1].powi(3)
+            }),
+            vec![
+                inequality(|x| -x[3] + x[2] - 0.55),
+                inequality(|x| -x[2] + x[3] - 0.55),
+                equality(|x| {
+                    1000.0 * (-x[2] - 0.25).sin() + 1000.0 * (-x[3] - 0.25).sin() + 894.8 - x[0]
+                }),
+                equality(|x| {
+                    1000.0 * (x[2] - 0.25).sin() + 1000.0 * (x[2] - x[3] - 0.25).sin() + 894.8
+                        - x[1]
+                }),
+                equality(|x| {
+                    1000.0 * (x[3] - 0.25).sin() + 1000.0 * (x[3] - x[2] - 0.25).sin() + 1294.8
+                }),
+            ],
+            5126.4967140071,
+            &[
+                679.9451482970287,
+                1026.066976000047,
+                0.11887636909441043,
+                -0.39623348521517826,
+            ],
+            vec![(0.0, 1200.0), (0.0, 1200.0), (-0.55, 0.55), (-0.55, 0.55)],
+        ),
+        constrained(
+            "g06",
+            Box::new(|x| (x[0] - 10.0).powi(3) + (x[1] - 20.0).powi(3)),
+            vec![
+                inequality(|x| -(x[0] - 5.0).powi(2) - (x[1] - 5.0).powi(2) + 100.0),
+                inequality(|x| (x[0] - 6.0).powi(2) + (x[1] - 5.0).powi(2) - 82.81),
+            ],
+            -6961.81387558015,
+            &[14.095, 0.8429607892154796],
+            vec![(13.0, 100.0), (0.0, 100.0)],
+        ),
+        constrained(
+            "g07",
+            Box::new(|x| {
+                x[0] * x[0] + x[1] * x[1] + x[0] * x[1] - 14.0 * x[0] - 16.0 * x[1]
+                    + (x[2] - 10.0).powi(2)
+                    + 4.0 * (x[3] - 5.0).powi(2)
+                    + (x[4] - 3.0).powi(2)
+                    + 2.0 * (x[5] - 1.0).powi(2)
+                    + 5.0 * x[6] * x[6]
+                    + 7.0 * (x[7] - 11.0).powi(2)
+                    + 2.0 * (x[8] - 10.0).powi(2)
+                    + (x[9] - 7.0).powi(2)
+                    + 45.0
+            }),
+            vec![
+                inequality(|x| -105.0 + 4.0 * x[0] + 5.0 * x[1] - 3.0 * x[6] + 9.0 * x[7]),
+                inequality(|x| 10.0 * x[0] - 8.0 * x[1] - 17.0 * x[6] + 2.0 * x[7]),
+                inequality(|x| -8.0 * x[0] + 2.0 * x[1] + 5.0 * x[8] - 2.0 * x[9] - 12.0),
+                inequality(|x| {
+                    3.0 * (x[0] - 2.0).powi(2) + 4.0 * (x[1] - 3.0).powi(2) + 2.0 * x[2] * x[2]
+                        - 7.0 * x[3]
+                        - 120.0
+                }),
+                inequality(|x| {
+                    5.0 * x[0] * x[0] + 8.0 * x[1] + (x[2] - 6.0).powi(2) - 2.0 * x[3] - 40.0
+                }),
+                inequality(|x| {
+                    x[0] * x[0] + 2.0 * (x[1] - 2.0).powi(2) - 2.0 * x[0] * x[1] + 14.0 * x[4]
+                        - 6.0 * x[5]
+                }),
+                inequality(|x| {
+                    0.5 * (x[0] - 8.0).powi(2) + 2.0 * (x[1] - 4.0).powi(2) + 3.0 * x[4] * x[4]
+                        - x[5]
+                        - 30.0
+                }),
+                inequality(|x| -3.0 * x[0] + 6.0 * x[1] + 12.0 * (x[8] - 8.0).powi(2) - 7.0 * x[9]),
+            ],
+            24.30620906818,
+            &[
+                2.17199634142692,
+                2.3636830416034,
+                8.77392573913157,
+                5.09598443745173,
+                0.990654756560493,
+                1.43057392853463,
+                1.32164415364306,
+                9.82872576524495,
+                8.2800915887356,
+                8.3759266477347,
+            ],
+            vec![(-10.0, 10.0); 10],
+        ),
+        constrained(
+            "g08",
+            Box::new(|x| {
+                -(2.0 * PI * x[0]).sin().powi(3) * (2.0 * PI * x[1]).sin()
+                    / (x[0].powi(3) * (x[0] + x[1]))
+            }),
+            vec![
+                inequality(|x| x[0] * x[0] - x[1] + 1.0),
+                inequality(|x| 1.0 - x[0] + (x[1] - 4.0).powi(2)),
+            ],
+            -0.0958250414180359,
+            &[1.227971352607526, 4.245373366122749],
+            vec![(0.0, 10.0); 2],
+        ),
+        constrained(
+            "g09",
+            Box::new(|x| {
+                (x[0] - 10.0).powi(2)
+                    + 5.0 * (x[1] - 12.0).powi(2)
+                    + x[2].powi(4)
+                    + 3.0 * (x[3] - 11.0).powi(2)
+                    + 10.0 * x[4].powi(6)
+                    + 7.0 * x[5] * x[5]
+                    + x[6].powi(4)
+                    - 4.0 * x[5] * x[6]
+                    - 10.0 * x[5]
+                    - 8.0 * x[6]
+            }),
+            vec![
+                inequality(|x| {
+                    -127.0
+                        + 2.0 * x[0] * x[0]
+                        + 3.0 * x[1].powi(4)
+                        + x[2]
+                        + 4.0 * x[3] * x[3]
+                        + 5.0 * x[4]
+                }),
+                inequality(|x| -282.0 + 7.0 * x[0] + 3.0 * x[1] + 10.0 * x[2] * x[2] + x[3] - x[4]),
+                inequality(|x| -196.0 + 23.0 * x[0] + x[1] * x[1] + 6.0 * x[5] * x[5] - 8.0 * x[6]),
+                inequality(|x| {
+                    4.0 * x[0] * x[0] + x[1] * x[1] - 3.0 * x[0] * x[1]
+                        + 2.0 * x[2] * x[2]
+                        + 5.0 * x[5]
+                        - 11.0 * x[6]
+                }),
+            ],
+            680.630057374402,
+            &[
+                2.3304993514740517,
+                1.951372368471146,
+                -0.4775413995106158,
+                4.365726249236259,
+                -0.624486959100389,
+                1.0381309941096217,
+                1.594226678067152,
+            ],
+            vec![(-10.0, 10.0); 7],
+        ),
+        constrained(
+            "g10",
+            Box::new(|x| x[0] + x[1] + x[2]),
+            vec![
+                inequality(|x| -1.0 + 0.0025 * (x[3] + x[5])),
+                inequality(|x| -1.0 + 0.0025 * (x[4] + x[6] - x[3])),
+                inequality(|x| -1.0 + 0.01 * (x[7] - x[4])),
+                inequality(|x| -x[0] * x[5] + 833.33252 * x[3] + 100.0 * x[0] - 83333.333),
+                inequality(|x| -x[1] * x[6] + 1250.0 * x[4] + x[1] * x[3] - 1250.0 * x[3]),
+                inequality(|x| -x[2] * x[7] + 1250000.0 + x[2] * x[4] - 2500.0 * x[4]),
+            ],
+            7049.24802052867,
+            &[
+                579.3066850179796,
+                1359.970678079356,
+                5109.970657431333,
+                182.01769963061534,
+                295.6011737027468,
+                217.98230036938463,
+                286.4165259278685,
+                395.60117370274673,
+            ],
+            [
+                vec![(100.0, 10000.0)],
+                vec![(1000.0, 10000.0); 2],
+                vec![(10.0, 1000.0); 5],
+            ]
+            .concat(),
+        ),
+        constrained(
+            "g11",
+            Box::new(|x| x[0] * x[0] + (x[1] - 1.0).powi(2)),
+            vec![equality(|x| x[1] - x[0] * x[0])],
+            0.75,
+            &[1.0 / 2.0_f64.sqrt(), 0.5],
+            vec![(-1.0, 1.0); 2],
+        ),
+        constrained(
+            "g12",
+            Box::new(|x| {
+                -(100.0 - (x[0] - 5.0).powi(2) - (x[1] - 5.0).powi(2) - (x[2] - 5.0).powi(2))
+                    / 100.0
+            }),
+            // the feasible region is the union of 729 disjoint spheres, so
+            // only the closest one has to be satisfied
+            vec![inequality(|x| {
+                (1..=9)
+                    .flat_map(|p| (1..=9).flat_map(move |q| (1..=9).map(move |r| (p, q, r))))
+                    .map(|(p, q, r)| {
+                        (x[0] - p as f64).powi(2)
+                            + (x[1] - q as f64).powi(2)
+                            + (x[2] - r as f64).powi(2)
+                            - 0.0625
+                    })
+                    .fold(f64::INFINITY, f64::min)
+            })],
+            -1.0,
+            &[5.0, 5.0, 5.0],
+            vec![(0.0, 10.0); 3],
+        ),
+        constrained(
+            "g13",
+            Box::new(|x| x.iter().product::<f64>().exp()),
+            vec![
+                equality(|x| x.iter().map(|x| x * x).sum::<f64>() - 10.0),
+                equality(|x| x[1] * x[2] - 5.0 * x[3] * x[4]),
+                equality(|x| x[0].powi(3) + x[1].powi(3) + 1.0),
+            ],
+            0.053941514041898,
+            &[
+                -1.71714224003,
+                1.59572124049468,
+                1.8272502406271,
+                -0.763659881912867,
+                -0.76365986736498,
+            ],
+            vec![
+                (-2.3, 2.3),
+                (-2.3, 2.3),
+                (-3.2, 3.2),
+                (-3.2, 3.2),
+                (-3.2, 3.2),
+            ],
+        ),
+        // shell thickness, head thickness, inner radius and length
+        constrained(
+            "pressure vessel",
+            Box::new(|x| {
+                0.6224 * x[0] * x[2] * x[3]
+                    + 1.7781 * x[1] * x[2] * x[2]
+                    + 3.1661 * x[0] * x[0] * x[3]
+                    + 19.84 * x[0] * x[0] * x[2]
+            }),
+            vec![
+                inequality(|x| -x[0] + 0.0193 * x[2]),
+                inequality(|x| -x[1] + 0.00954 * x[2]),
+                inequality(|x| {
+                    -PI * x[2] * x[2] * x[3] - 4.0 / 3.0 * PI * x[2].powi(3) + 1296000.0
+                }),
+                inequality(|x| x[3] - 240.0),
+            ],
+            5885.3327736,
+            &[0.7781686, 0.3846491, 40.3196187, 200.0],
+            vec![(0.0, 99.0), (0.0, 99.0), (10.0, 200.0), (10.0, 200.0)],
+        ),
+        // weld thickness, weld length, bar thickness and bar breadth
+        constrained(
+            "welded beam",
+            Box::new(|x| 1.10471 * x[0] * x[0] * x[1] + 0.04811 * x[2] * x[3] * (14.0 + x[1])),
+            vec![
+                inequality(|x| beam(x).shear - 13600.0),
+                inequality(|x| beam(x).stress - 30000.0),
+                inequality(|x| x[0] - x[3]),
+                inequality(|x| 0.10471 * x[0] * x[0] + 0.04811 * x[2] * x[3] * (14.0 + x[1]) - 5.0),
+                inequality(|x| 0.125 - x[0]),
+                inequality(|x| beam(x).deflection - 0.25),
+                inequality(|x| 6000.0 - beam(x).buckling),
+            ],
+            1.724852308597366,
+            &[0.20572963978, 3.47048866563, 9.03662391036, 0.20572963979],
+            vec![(0.1, 2.0), (0.1, 10.0), (0.1, 10.0), (0.1, 2.0)],
+        ),
+        // wire diameter, coil diameter and number of active coils
+        constrained(
+            "tension spring",
+            Box::new(|x| (x[2] + 2.0) * x[1] * x[0] * x[0]),
+            vec![
+                inequality(|x| 1.0 - x[1].powi(3) * x[2] / (71785.0 * x[0].powi(4))),
+                inequality(|x| {
+                    (4.0 * x[1] * x[1] - x[0] * x[1])
+                        / (12566.0 * (x[1] * x[0].powi(3) - x[0].powi(4)))
+                        + 1.0 / (5108.0 * x[0] * x[0])
+                        - 1.0
+                }),
+                inequality(|x| 1.0 - 140.45 * x[0] / (x[1] * x[1] * x[2])),
+                inequality(|x| (x[0] + x[1]) / 1.5 - 1.0),
+            ],
+            0.012665232788,
+            &[0.051689061, 0.356717736, 11.288964941],
+            vec![(0.05, 2.0), (0.25, 1.3), (2.0, 15.0)],
+        ),
+    ]
+}
+
+/// Terms of the first pair of constraints of g04.
+fn u(x: &[f64]) -> f64 {
+    85.334407 + 0.0056858 * x[1] * x[4] + 0.0006262 * x[0] * x[3] - 0.0022053 * x[2] * x[4]
+}
+
+/// Terms of the second pair of constraints of g04.
+fn v(x: &[f64]) -> f64 {
+    80.51249 + 0.0071317 * x[1] * x[4] + 0.0029955 * x[0] * x[1] + 0.0021813 * x[2] * x[2]
+}
+
+/// Terms of the third pair of constraints of g04.
+fn w(x: &[f64]) -> f64 {
+    9.300961 + 0.0047026 * x[2] * x[4] + 0.0012547 * x[0] * x[2] + 0.0019085 * x[2] * x[3]
+}
+
+/// Response of the welded beam to its load.
+struct Beam {
+    shear: f64,
+    stress: f64,
+    deflection: f64,
+    /// critical buckling load
+    buckling: f64,
+}
+
+fn beam(x: &[f64]) -> Beam {
+    const P: f64 = 6000.0;
+    const L: f64 = 14.0;
+    const E: f64 = 30e6;
+    const G: f64 = 12e6;
+    let (h, l, t, b) = (x[0], x[1], x[2], x[3]);
+
+    let primary = P / (2.0_f64.sqrt() * h * l);
+    let moment = P * (L + l / 2.0);
+    let r = (l * l / 4.0 + ((h + t) / 2.0).powi(2)).sqrt();
+    let j = 2.0 * (2.0_f64.sqrt() * h * l * (l * l / 12.0 + ((h + t) / 2.0).powi(2)));
+    let secondary = moment * r / j;
+
+    Beam {
+        shear: (primary * primary + primary * secondary * l / r + secondary * secondary).sqrt(),
+        stress: 6.0 * P * L / (b * t * t),
+        deflection: 4.0 * P * L.powi(3) / (E * t.powi(3) * b),
+        buckling: 4.013 * E * (t * t * b.powi(6) / 36.0).sqrt() / (L * L)
+            * (1.0 - t / (2.0 * L) * (E / (4.0 * G)).sqrt()),
+    }
+}
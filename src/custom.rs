@@ -0,0 +1,559 @@
+use crate::function::Function;
+use mikes_pso::{bounds::Bound, vector::Vector};
+use std::f64::consts::{E, PI};
+use std::fs;
+
+/// User-defined benchmark functions read from `path`, one declaration per
+/// line of the form
+///
+/// ```text
+/// name; expression; lower, upper; minimum[; optimum]
+/// ```
+///
+/// where blank lines and lines starting with `#` are skipped. The expression
+/// is a formula over the coordinates `x[k]`, indexed from one, and the
+/// dimension `n` with the usual arithmetic, `^` for powers, the constants
+/// `pi` and `e`, numbers with an exponent such as `1e-6` and the functions `sin`, `cos`, `tan`, `exp`, `ln`, `sqrt`
+/// and `abs`. `sum(body)` and `prod(body)` reduce `body` over the index `i`
+/// from 1 to `n`, and `sum(j = from..to, body)` over any index and inclusive
+/// range, so that for example Rosenbrock is
+///
+/// ```text
+/// rosenbrock; sum(i = 1..n - 1, 100 (x[i + 1] - x[i]^2)^2 + (x[i] - 1)^2); -30, 30; 0; 1
+/// ```
+///
+/// The bounds and the minimum may depend on `n`, and the optional optimum is
+/// the value of every coordinate as a formula over its index `i`. Every
+/// formula is evaluated once when it is read, so that a coordinate or range
+/// outside of the dimension fails when the functions are loaded.
+pub fn functions<const SIZE: usize>(path: &str) -> Vec<Function<SIZE>> {
+    fs::read_to_string(path)
+        .unwrap_or_else(|error| panic!("Could not read {path}: {error}"))
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(declaration)
+        .collect()
+}
+
+/// Compiles one declaration into a function.
+fn declaration<const SIZE: usize>(line: &str) -> Function<SIZE> {
+    let fields = line.split(';').map(str::trim).collect::<Vec<_>>();
+    let (name, expression, bounds, minimum, optimum) = match fields[..] {
+        [name, expression, bounds, minimum] => (name, expression, bounds, minimum, None),
+        [name, expression, bounds, minimum, optimum] => {
+            (name, expression, bounds, minimum, Some(optimum))
+        }
+        _ => panic!("Expected `name; expression; lower, upper; minimum[; optimum]`: {line}"),
+    };
+    let (lower, upper) = bounds
+        .split_once(',')
+        .unwrap_or_else(|| panic!("Expected `lower, upper` bounds: {bounds}"));
+
+    let origin = Vector::from([0.0; SIZE]);
+    let evaluate = |formula: &Formula, indices: &mut Vec<f64>| {
+        formula
+            .evaluate(&origin, indices)
+            .unwrap_or_else(|error| panic!("{error} in `{line}`"))
+    };
+    let formula = Formula::parse(expression, &[]);
+    evaluate(&formula, &mut Vec::new());
+    // the bounds, minimum and optimum are evaluated once, never reading the
+    // coordinates
+    let constant = |source: &str| evaluate(&Formula::parse(source, &[]), &mut Vec::new());
+    let optima = optimum
+        .map(|optimum| {
+            let optimum = Formula::parse(optimum, &["i"]);
+            Vector::from(std::array::from_fn(|d| {
+                evaluate(&optimum, &mut vec![(d + 1) as f64])
+            }))
+        })
+        .into_iter()
+        .collect();
+
+    let name = name.to_string();
+    Function {
+        name: name.clone(),
+        func: Box::new(move |coords: &Vector<SIZE>| {
+            formula
+                .evaluate(coords, &mut Vec::new())
+                .unwrap_or_else(|error| panic!("{error} in {name}"))
+        }),
+        batch: None,
+        truth: None,
+        minima: constant(minimum),
+        optima,
+        bounds: vec![Bound::from((constant(lower), constant(upper))); SIZE],
+        dimension: None,
+        constraints: Vec::new(),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Power,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Call {
+    Sin,
+    Cos,
+    Tan,
+    Exp,
+    Ln,
+    Sqrt,
+    Abs,
+}
+
+/// A parsed expression, whose indices refer to the reductions enclosing them
+/// by their depth.
+#[derive(Debug)]
+enum Formula {
+    Number(f64),
+    Dimension,
+    Index(usize),
+    /// the coordinate at a one-indexed position
+    Coordinate(Box<Formula>),
+    Negate(Box<Formula>),
+    Binary(Operator, Box<Formula>, Box<Formula>),
+    Call(Call, Box<Formula>),
+    /// the sum, or product, of the body over every index of the inclusive
+    /// range
+    Reduce {
+        product: bool,
+        from: Box<Formula>,
+        to: Box<Formula>,
+        body: Box<Formula>,
+    },
+}
+
+impl Formula {
+    /// Parses `source` in which the names of `indices` are already bound,
+    /// panicking with the position of the first error.
+    fn parse(source: &str, indices: &[&str]) -> Self {
+        let mut parser = Parser {
+            source,
+            tokens: tokenise(source),
+            position: 0,
+            scope: indices.iter().map(|index| index.to_string()).collect(),
+        };
+        let formula = parser.sum();
+        if parser.position < parser.tokens.len() {
+            parser.fail("Expected the end of the expression");
+        }
+
+        formula
+    }
+
+    /// Value at `coords` with the enclosing indices in `indices`, failing
+    /// when a coordinate or range is outside of the dimension.
+    fn evaluate<const SIZE: usize>(
+        &self,
+        coords: &Vector<SIZE>,
+        indices: &mut Vec<f64>,
+    ) -> Result<f64, String> {
+        Ok(match self {
+            Formula::Number(value) => *value,
+            Formula::Dimension => SIZE as f64,
+            Formula::Index(depth) => indices[*depth],
+            Formula::Coordinate(k) => {
+                let k = k.evaluate(coords, indices)?.round();
+                // also rejects a NaN index
+                if !(1.0..=SIZE as f64).contains(&k) {
+                    return Err(format!("Coordinate x[{k}] is outside of x[1] to x[{SIZE}]"));
+                }
+                coords[k as usize - 1]
+            }
+            Formula::Negate(x) => -x.evaluate(coords, indices)?,
+            Formula::Binary(operator, x, y) => {
+                let (x, y) = (x.evaluate(coords, indices)?, y.evaluate(coords, indices)?);
+                match operator {
+                    Operator::Add => x + y,
+                    Operator::Subtract => x - y,
+                    Operator::Multiply => x * y,
+                    Operator::Divide => x / y,
+                    Operator::Power => x.powf(y),
+                }
+            }
+            Formula::Call(call, x) => {
+                let x = x.evaluate(coords, indices)?;
+                match call {
+                    Call::Sin => x.sin(),
+                    Call::Cos => x.cos(),
+                    Call::Tan => x.tan(),
+                    Call::Exp => x.exp(),
+                    Call::Ln => x.ln(),
+                    Call::Sqrt => x.sqrt(),
+                    Call::Abs => x.abs(),
+                }
+            }
+            Formula::Reduce {
+                product,
+                from,
+                to,
+                body,
+            } => {
+                let from = from.evaluate(coords, indices)?.round();
+                let to = to.evaluate(coords, indices)?.round();
+                if !from.is_finite() || !to.is_finite() {
+                    return Err(format!("Range {from}..{to} is not finite"));
+                }
+                let mut total = if *product { 1.0 } else { 0.0 };
+                indices.push(0.0);
+                for index in from as i64..=to as i64 {
+                    *indices.last_mut().unwrap() = index as f64;
+                    let value = body.evaluate(coords, indices)?;
+                    if *product {
+                        total *= value;
+                    } else {
+                        total += value;
+                    }
+                }
+                indices.pop();
+
+                total
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Symbol(char),
+    /// the `..` of a range
+    Range,
+}
+
+/// Splits `source` into tokens along with the byte offset of each.
+fn tokenise(source: &str) -> Vec<(usize, Token)> {
+    let chars = source.char_indices().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (start, c) = chars[i];
+        let digit = |i: usize| chars.get(i).is_some_and(|(_, c)| c.is_ascii_digit());
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || (c == '.' && digit(i + 1)) {
+            // a `.` only continues a number when followed by a digit, so
+            // that `1..n` is a range
+            while digit(i) || (chars.get(i).is_some_and(|(_, c)| *c == '.') && digit(i + 1)) {
+                i += 1;
+            }
+            // and an `e` only starts an exponent when followed by digits, so
+            // that `2e` is still `2 * e`
+            if chars.get(i).is_some_and(|(_, c)| *c == 'e' || *c == 'E') {
+                let sign = chars
+                    .get(i + 1)
+                    .is_some_and(|(_, c)| *c == '+' || *c == '-');
+                let first = i + 1 + sign as usize;
+                if digit(first) {
+                    i = first;
+                    while digit(i) {
+                        i += 1;
+                    }
+                }
+            }
+            let end = chars.get(i).map_or(source.len(), |(end, _)| *end);
+            tokens.push((start, Token::Number(source[start..end].parse().unwrap())));
+        } else if c.is_alphabetic() || c == '_' {
+            while chars
+                .get(i)
+                .is_some_and(|(_, c)| c.is_alphanumeric() || *c == '_')
+            {
+                i += 1;
+            }
+            let end = chars.get(i).map_or(source.len(), |(end, _)| *end);
+            tokens.push((start, Token::Name(source[start..end].to_string())));
+        } else if c == '.' && chars.get(i + 1).is_some_and(|(_, c)| *c == '.') {
+            tokens.push((start, Token::Range));
+            i += 2;
+        } else if "+-*/^()[],=".contains(c) {
+            tokens.push((start, Token::Symbol(c)));
+            i += 1;
+        } else {
+            panic!("Unexpected `{c}` at {start} in `{source}`");
+        }
+    }
+
+    tokens
+}
+
+/// Recursive descent parser over the tokens, where `scope` holds the names of
+/// the enclosing indices from the outermost.
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+    scope: Vec<String>,
+}
+
+impl Parser<'_> {
+    fn fail(&self, message: &str) -> ! {
+        let at = self
+            .tokens
+            .get(self.position)
+            .map_or(self.source.len(), |(at, _)| *at);
+        panic!("{message} at {at} in `{}`", self.source);
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek().cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        let found = self.peek() == Some(&Token::Symbol(symbol));
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, symbol: char) {
+        if !self.eat(symbol) {
+            self.fail(&format!("Expected `{symbol}`"));
+        }
+    }
+
+    /// `product (("+" | "-") product)*`
+    fn sum(&mut self) -> Formula {
+        let mut formula = self.product();
+        loop {
+            let operator = if self.eat('+') {
+                Operator::Add
+            } else if self.eat('-') {
+                Operator::Subtract
+            } else {
+                return formula;
+            };
+            formula = Formula::Binary(operator, Box::new(formula), Box::new(self.product()));
+        }
+    }
+
+    /// `unary (("*" | "/")? unary)*`, where a missing operator multiplies as
+    /// in `2 x[i]`
+    fn product(&mut self) -> Formula {
+        let mut formula = self.unary();
+        loop {
+            let operator = if self.eat('*') {
+                Operator::Multiply
+            } else if self.eat('/') {
+                Operator::Divide
+            } else if matches!(
+                self.peek(),
+                Some(Token::Number(_) | Token::Name(_) | Token::Symbol('('))
+            ) {
+                Operator::Multiply
+            } else {
+                return formula;
+            };
+            formula = Formula::Binary(operator, Box::new(formula), Box::new(self.unary()));
+        }
+    }
+
+    /// `"-" unary | power`, so that `-x^2` is `-(x^2)`
+    fn unary(&mut self) -> Formula {
+        if self.eat('-') {
+            Formula::Negate(Box::new(self.unary()))
+        } else {
+            self.power()
+        }
+    }
+
+    /// `atom ("^" unary)?`, which is right associative
+    fn power(&mut self) -> Formula {
+        let base = self.atom();
+        if self.eat('^') {
+            Formula::Binary(Operator::Power, Box::new(base), Box::new(self.unary()))
+        } else {
+            base
+        }
+    }
+
+    fn atom(&mut self) -> Formula {
+        match self.next() {
+            Some(Token::Number(value)) => Formula::Number(value),
+            Some(Token::Symbol('(')) => {
+                let formula = self.sum();
+                self.expect(')');
+                formula
+            }
+            Some(Token::Name(name)) => {
+                if let Some(depth) = self.scope.iter().rposition(|index| *index == name) {
+                    return Formula::Index(depth);
+                }
+                match name.as_str() {
+                    "n" => Formula::Dimension,
+                    "pi" => Formula::Number(PI),
+                    "e" => Formula::Number(E),
+                    "x" => {
+                        self.expect('[');
+                        let k = self.sum();
+                        self.expect(']');
+                        Formula::Coordinate(Box::new(k))
+                    }
+                    "sum" | "prod" => self.reduce(name == "prod"),
+                    _ => {
+                        let call = match name.as_str() {
+                            "sin" => Call::Sin,
+                            "cos" => Call::Cos,
+                            "tan" => Call::Tan,
+                            "exp" => Call::Exp,
+                            "ln" => Call::Ln,
+                            "sqrt" => Call::Sqrt,
+                            "abs" => Call::Abs,
+                            _ => {
+                                self.position -= 1;
+                                self.fail(&format!("Unknown name `{name}`"));
+                            }
+                        };
+                        self.expect('(');
+                        let x = self.sum();
+                        self.expect(')');
+                        Formula::Call(call, Box::new(x))
+                    }
+                }
+            }
+            _ => {
+                self.position -= 1;
+                self.fail("Expected a number, name or `(`");
+            }
+        }
+    }
+
+    /// `"(" (name "=" sum ".." sum ",")? sum ")"` after `sum` or `prod`, with
+    /// the index `i` from 1 to `n` when no range is given.
+    fn reduce(&mut self, product: bool) -> Formula {
+        self.expect('(');
+        let ranged = matches!(
+            self.tokens.get(self.position..self.position + 2),
+            Some([(_, Token::Name(_)), (_, Token::Symbol('='))])
+        );
+        let (index, from, to) = if ranged {
+            let Some(Token::Name(index)) = self.next() else {
+                unreachable!()
+            };
+            self.expect('=');
+            let from = self.sum();
+            if self.next() != Some(Token::Range) {
+                self.position -= 1;
+                self.fail("Expected `..`");
+            }
+            let to = self.sum();
+            self.expect(',');
+            (index, from, to)
+        } else {
+            ("i".to_string(), Formula::Number(1.0), Formula::Dimension)
+        };
+
+        self.scope.push(index);
+        let body = self.sum();
+        self.scope.pop();
+        self.expect(')');
+
+        Formula::Reduce {
+            product,
+            from: Box::new(from),
+            to: Box::new(to),
+            body: Box::new(body),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Value of `source` at the coordinates 1, 2 and 3.
+    fn value(source: &str) -> Result<f64, String> {
+        Formula::parse(source, &[]).evaluate(&Vector::from([1.0, 2.0, 3.0]), &mut Vec::new())
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(value("1 + 2 * 3"), Ok(7.0));
+        assert_eq!(value("(1 + 2) * 3"), Ok(9.0));
+        assert_eq!(value("8 / 4 / 2"), Ok(1.0));
+        assert_eq!(value("2 ^ 3 ^ 2"), Ok(512.0));
+        assert_eq!(value("2 x[3] + 1"), Ok(7.0));
+        assert_eq!(value("2 pi"), Ok(2.0 * PI));
+    }
+
+    #[test]
+    fn negation() {
+        assert_eq!(value("-x[2]^2"), Ok(-4.0));
+        assert_eq!(value("-2^2"), Ok(-4.0));
+        assert_eq!(value("2^-1"), Ok(0.5));
+        assert_eq!(value("1 - -x[1]"), Ok(2.0));
+    }
+
+    #[test]
+    fn ranges() {
+        assert_eq!(value("sum(x[i])"), Ok(6.0));
+        assert_eq!(value("prod(x[i])"), Ok(6.0));
+        assert_eq!(value("sum(j = 2..n, x[j])"), Ok(5.0));
+        assert_eq!(value("sum(i = 1..n - 1, x[i + 1] - x[i])"), Ok(2.0));
+        assert_eq!(value("sum(j = 3..2, 1)"), Ok(0.0));
+        assert_eq!(value("prod(j = 3..2, 2)"), Ok(1.0));
+    }
+
+    #[test]
+    fn nested_indices() {
+        assert_eq!(value("sum(i = 1..n, sum(j = 1..i, x[j]))"), Ok(10.0));
+        // the inner `i` shadows the outer one
+        assert_eq!(value("sum(sum(x[i]))"), Ok(18.0));
+        assert_eq!(value("x[x[1] + 1]"), Ok(2.0));
+        assert_eq!(value("x[x[x[1] + 1]]"), Ok(2.0));
+    }
+
+    #[test]
+    fn scientific_notation() {
+        assert_eq!(value("1e3"), Ok(1000.0));
+        assert_eq!(value("2.5E-2"), Ok(0.025));
+        assert_eq!(value("1e+2"), Ok(100.0));
+        assert_eq!(value("2e-1"), Ok(0.2));
+        assert_eq!(value("2e"), Ok(2.0 * E));
+        assert_eq!(value("2 e - 1"), Ok(2.0 * E - 1.0));
+    }
+
+    #[test]
+    fn outside_of_the_dimension() {
+        assert!(value("x[4]").is_err());
+        assert!(value("x[0]").is_err());
+        assert!(value("x[0 / 0]").is_err());
+        assert!(value("sum(j = 1..1 / 0, 1)").is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown name `foo`")]
+    fn unknown_name() {
+        Formula::parse("foo(1)", &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Coordinate x[4] is outside of x[1] to x[3] in `bad;")]
+    fn fails_at_load_time() {
+        declaration::<3>("bad; sum(x[i + 1]); -1, 1; 0");
+    }
+
+    #[test]
+    fn rosenbrock() {
+        let function = declaration::<3>(
+            "rosenbrock; sum(i = 1..n - 1, 100 (x[i + 1] - x[i]^2)^2 + (x[i] - 1)^2); -30, 30; 0; 1",
+        );
+        assert_eq!((function.func)(&function.optima[0]), 0.0);
+        assert_eq!((function.func)(&Vector::from([0.0; 3])), 2.0);
+    }
+}
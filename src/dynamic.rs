@@ -0,0 +1,265 @@
+use crate::baseline::gaussian;
+use crate::evolver::Expression;
+use crate::function::{self, Function};
+use crate::swarm::{pso, Settings};
+use crate::velocity::Velocity;
+use mikes_ge::grammar::Grammar;
+use mikes_pso::{bounds::Bound, vector::Vector};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::cell::{Cell, RefCell};
+use std::fmt::{self, Display};
+
+/// Default number of evaluations between changes of the landscape.
+pub const PERIOD: usize = 5000;
+
+/// How the landscape of a dynamic benchmark starts and changes.
+enum Landscape<const SIZE: usize> {
+    /// the moving peaks benchmark of Branke with `count` cones, whose
+    /// heights, widths and positions change as in its second scenario
+    Peaks { count: usize },
+    /// a static function whose optimum drifts by a gaussian step of
+    /// `severity` times half of every bound range
+    Shifting {
+        function: Function<SIZE>,
+        severity: f64,
+    },
+}
+
+/// A benchmark whose landscape changes every `period` evaluations.
+pub struct Dynamic<const SIZE: usize> {
+    pub name: String,
+    pub bounds: Vec<Bound>,
+    pub period: usize,
+    landscape: Landscape<SIZE>,
+}
+
+/// The moving peaks benchmark followed by a shifting variant of every
+/// scalable, noiseless classic function, all changing every `period`
+/// evaluations.
+pub fn benchmarks<const SIZE: usize>(period: usize) -> Vec<Dynamic<SIZE>> {
+    let mut benchmarks = vec![Dynamic {
+        name: "moving peaks".to_string(),
+        bounds: vec![Bound::from((0.0, 100.0)); SIZE],
+        period,
+        landscape: Landscape::Peaks { count: 10 },
+    }];
+    benchmarks.extend(
+        function::functions::<SIZE>()
+            .into_iter()
+            .filter(|function| function.fits() && function.truth.is_none())
+            .map(|function| Dynamic {
+                name: format!("shifting {}", function.name),
+                bounds: function.bounds.clone(),
+                period,
+                landscape: Landscape::Shifting {
+                    function,
+                    severity: 0.1,
+                },
+            }),
+    );
+
+    benchmarks
+}
+
+struct Peak<const SIZE: usize> {
+    position: [f64; SIZE],
+    height: f64,
+    width: f64,
+}
+
+/// The landscape between two changes.
+enum State<const SIZE: usize> {
+    Peaks(Vec<Peak<SIZE>>),
+    /// offset of the optimum from its original position
+    Shifting([f64; SIZE]),
+}
+
+/// A single run through the changes of a dynamic benchmark, which tracks the
+/// error of the best fitness found since the last change after every
+/// evaluation.
+pub struct Environment<'a, const SIZE: usize> {
+    dynamic: &'a Dynamic<SIZE>,
+    rng: RefCell<StdRng>,
+    state: RefCell<State<SIZE>>,
+    evaluations: Cell<usize>,
+    /// best fitness since the last change
+    best: Cell<f64>,
+    /// sum of the error after every evaluation
+    error: Cell<f64>,
+    /// error just before every change
+    before: RefCell<Vec<f64>>,
+}
+
+impl<const SIZE: usize> Dynamic<SIZE> {
+    /// A fresh run of the benchmark whose landscape is drawn from a generator
+    /// seeded by `seed`, so that runs with the same seed see the same
+    /// changes.
+    pub fn environment(&self, seed: u64) -> Environment<'_, SIZE> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let state = match &self.landscape {
+            Landscape::Peaks { count } => State::Peaks(
+                (0..*count)
+                    .map(|_| Peak {
+                        position: std::array::from_fn(|d| {
+                            rng.gen_range(self.bounds[d].lower()..=self.bounds[d].upper())
+                        }),
+                        height: 50.0,
+                        width: rng.gen_range(1.0..=12.0),
+                    })
+                    .collect(),
+            ),
+            Landscape::Shifting { .. } => State::Shifting([0.0; SIZE]),
+        };
+
+        Environment {
+            dynamic: self,
+            rng: RefCell::new(rng),
+            state: RefCell::new(state),
+            evaluations: Cell::new(0),
+            best: Cell::new(f64::INFINITY),
+            error: Cell::new(0.0),
+            before: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl<const SIZE: usize> Environment<'_, SIZE> {
+    /// Fitness of `coords` in the current landscape, changing the landscape
+    /// first when a period has passed.
+    pub fn evaluate(&self, coords: &Vector<SIZE>) -> f64 {
+        let evaluations = self.evaluations.get();
+        if evaluations > 0 && evaluations.is_multiple_of(self.dynamic.period) {
+            self.change();
+        }
+
+        let fitness = match (&*self.state.borrow(), &self.dynamic.landscape) {
+            // the peaks are maximised, so their negation is minimised
+            (State::Peaks(peaks), _) => -peaks
+                .iter()
+                .map(|peak| {
+                    let distance = (0..SIZE)
+                        .map(|d| (coords[d] - peak.position[d]).powi(2))
+                        .sum::<f64>()
+                        .sqrt();
+                    peak.height - peak.width * distance
+                })
+                .fold(f64::MIN, f64::max),
+            (State::Shifting(offset), Landscape::Shifting { function, .. }) => {
+                (function.func)(&Vector::from(std::array::from_fn(|d| {
+                    coords[d] - offset[d]
+                })))
+            }
+            _ => unreachable!(),
+        };
+
+        self.evaluations.set(evaluations + 1);
+        self.best.set(self.best.get().min(fitness));
+        self.error
+            .set(self.error.get() + self.best.get() - self.optimum());
+
+        fitness
+    }
+
+    /// Fitness of the optimum of the current landscape.
+    fn optimum(&self) -> f64 {
+        match (&*self.state.borrow(), &self.dynamic.landscape) {
+            (State::Peaks(peaks), _) => -peaks
+                .iter()
+                .map(|peak| peak.height)
+                .fold(f64::MIN, f64::max),
+            (_, Landscape::Shifting { function, .. }) => function.minima,
+            _ => unreachable!(),
+        }
+    }
+
+    fn change(&self) {
+        self.before
+            .borrow_mut()
+            .push(self.best.get() - self.optimum());
+        self.best.set(f64::INFINITY);
+
+        let rng = &mut *self.rng.borrow_mut();
+        let bounds = &self.dynamic.bounds;
+        match (&mut *self.state.borrow_mut(), &self.dynamic.landscape) {
+            (State::Peaks(peaks), _) => {
+                for peak in peaks {
+                    peak.height = (peak.height + 7.0 * gaussian(rng)).clamp(30.0, 70.0);
+                    peak.width = (peak.width + gaussian(rng)).clamp(1.0, 12.0);
+
+                    // a step of length one in a random direction, mirrored
+                    // back into the bounds
+                    let direction: [f64; SIZE] = std::array::from_fn(|_| gaussian(rng));
+                    let length = direction.iter().map(|x| x * x).sum::<f64>().sqrt();
+                    for (d, step) in direction.iter().enumerate() {
+                        let (lower, upper) = (bounds[d].lower(), bounds[d].upper());
+                        let x = peak.position[d] + step / length;
+                        peak.position[d] = if x < lower {
+                            2.0 * lower - x
+                        } else if x > upper {
+                            2.0 * upper - x
+                        } else {
+                            x
+                        };
+                    }
+                }
+            }
+            (State::Shifting(offset), Landscape::Shifting { severity, .. }) => {
+                for (d, offset) in offset.iter_mut().enumerate() {
+                    let half = (bounds[d].upper() - bounds[d].lower()) / 2.0;
+                    *offset =
+                        (*offset + severity * half * gaussian(rng)).clamp(-0.8 * half, 0.8 * half);
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Mean over every evaluation of the error of the best fitness found
+    /// since the last change.
+    pub fn offline_error(&self) -> f64 {
+        self.error.get() / self.evaluations.get() as f64
+    }
+
+    /// Mean error of the best fitness found just before every change,
+    /// including the end of the run.
+    pub fn best_before_change(&self) -> f64 {
+        let before = self.before.borrow();
+        let last = self.best.get() - self.optimum();
+
+        (before.iter().sum::<f64>() + last) / (before.len() + 1) as f64
+    }
+}
+
+/// An evolved velocity rule whose output is the offline error of a run on a
+/// dynamic benchmark, so that the evolver drives it to zero.
+pub struct Tracking<'a, const SIZE: usize>(pub Velocity<'a, SIZE>);
+
+impl<'a, const SIZE: usize> Grammar for Tracking<'a, SIZE> {
+    type Input = (&'a Dynamic<SIZE>, Settings);
+    type Output = f64;
+
+    fn run(&self, (dynamic, settings): &Self::Input) -> Self::Output {
+        let environment = dynamic.environment(rand::random());
+        pso(settings, &dynamic.bounds, &self.0, &|coords| {
+            environment.evaluate(coords)
+        });
+
+        environment.offline_error()
+    }
+
+    fn generate(chromosome: &[u8]) -> Self {
+        Self(Velocity::generate(chromosome))
+    }
+}
+
+impl<'a, const SIZE: usize> Display for Tracking<'a, SIZE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<'a, const SIZE: usize> Expression for Tracking<'a, SIZE> {
+    fn size(&self) -> usize {
+        self.0.size()
+    }
+}
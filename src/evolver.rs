@@ -0,0 +1,265 @@
+use mikes_ge::ge::GE;
+use mikes_ge::grammar::Grammar;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::sync::Mutex;
+
+/// A phenotype which can be measured and printed for the evolution trace.
+pub trait Expression: Display {
+    /// Number of nodes in the expression tree.
+    fn size(&self) -> usize;
+}
+
+/// Summary of a single generation of the evolver.
+#[derive(Debug)]
+pub struct Generation {
+    pub best: f64,
+    /// mean over the individuals with a finite fitness, infinite when none
+    /// of them has one
+    pub mean: f64,
+    pub worst: f64,
+    /// fraction of unique chromosomes in the population
+    pub genotype_diversity: f64,
+    /// fraction of unique expressions in the population
+    pub phenotype_diversity: f64,
+    /// mean number of nodes per expression
+    pub size: f64,
+    pub expression: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Trace {
+    pub generations: Vec<Generation>,
+}
+
+impl Trace {
+    /// Rows of `generation, best, mean, worst, genotype_diversity,
+    /// phenotype_diversity, size, expression`.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        for (i, g) in self.generations.iter().enumerate() {
+            csv.push_str(&format!(
+                "{i}, {}, {}, {}, {:.4}, {:.4}, {:.4}, \"{}\"\n",
+                g.best,
+                g.mean,
+                g.worst,
+                g.genotype_diversity,
+                g.phenotype_diversity,
+                g.size,
+                g.expression
+            ));
+        }
+
+        csv
+    }
+}
+
+/// `mikes_ge::ge::GE` with a trace of every generation, minimising the
+/// error between the output of each individual and the expected output of
+/// every training case.
+///
+/// The search is left entirely to `GE`, this only observes it. Every
+/// individual is wrapped so that its squared error on each training case is
+/// recorded as `GE` runs it, and an individual is complete once it has run
+/// every case. The trace assumes `GE` evaluates each individual of a
+/// generation on every case before moving on, so each `population`
+/// completed individuals form one generation and a trailing partial
+/// generation is dropped. The reported fitness is the mean squared error,
+/// whatever measure `GE` selects on.
+pub struct Evolver<'a, G: Grammar> {
+    population: usize,
+    rates: (f64, f64, f64),
+    tournament: usize,
+    length: (usize, usize),
+    wraps: usize,
+    generations: usize,
+    train: &'a [(G::Input, G::Output)],
+}
+
+impl<'a, G> Evolver<'a, G>
+where
+    G: Grammar<Output = f64> + Expression,
+{
+    /// Takes the same parameters as `GE::new`, which it passes on unchanged.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        population: usize,
+        rates: (f64, f64, f64),
+        tournament: usize,
+        min: usize,
+        max: usize,
+        wraps: usize,
+        generations: usize,
+        train: &'a [(G::Input, G::Output)],
+    ) -> Self {
+        Self {
+            population,
+            rates,
+            tournament,
+            length: (min, max),
+            wraps,
+            generations,
+            train,
+        }
+    }
+
+    /// Runs `GE` and returns the best chromosome it found along with a trace
+    /// of every generation.
+    pub fn start(&mut self) -> (Vec<u8>, Trace) {
+        let recorder = Recorder {
+            cases: self.train.len(),
+            population: self.population,
+            state: Mutex::default(),
+        };
+        let train = self
+            .train
+            .iter()
+            .map(|(input, expected)| ((input, *expected, &recorder), *expected))
+            .collect::<Vec<_>>();
+
+        let mut ge = GE::<_, f64, Observed<G>>::new(
+            self.population,
+            self.rates,
+            self.tournament,
+            self.length.0,
+            self.length.1,
+            self.wraps,
+            self.generations,
+            &train,
+        );
+        let chromosome = ge.start();
+
+        let generations = recorder.state.into_inner().unwrap().generations;
+        (chromosome, Trace { generations })
+    }
+}
+
+struct Individual {
+    chromosome: Vec<u8>,
+    fitness: f64,
+    expression: String,
+    size: usize,
+}
+
+#[derive(Default)]
+struct State {
+    current: Vec<Individual>,
+    generations: Vec<Generation>,
+}
+
+/// Collects completed individuals into generations.
+struct Recorder {
+    cases: usize,
+    population: usize,
+    state: Mutex<State>,
+}
+
+impl Recorder {
+    fn record(&self, individual: Individual) {
+        let mut state = self.state.lock().unwrap();
+        state.current.push(individual);
+        if state.current.len() == self.population {
+            let generation = summarise(&state.current);
+            state.generations.push(generation);
+            state.current.clear();
+        }
+    }
+}
+
+/// A phenotype of `G` which records its error on every training case it is
+/// run on.
+struct Observed<'a, G: Grammar> {
+    chromosome: Vec<u8>,
+    phenotype: G,
+    errors: Mutex<Vec<f64>>,
+    recorder: std::marker::PhantomData<&'a Recorder>,
+}
+
+impl<'a, G> Grammar for Observed<'a, G>
+where
+    G: Grammar<Output = f64> + Expression,
+    G::Input: 'a,
+{
+    type Input = (&'a G::Input, f64, &'a Recorder);
+    type Output = f64;
+
+    fn run(&self, (input, expected, recorder): &Self::Input) -> f64 {
+        let output = self.phenotype.run(input);
+        let mut errors = self.errors.lock().unwrap();
+        errors.push((output - expected).powi(2));
+
+        // an individual run again, such as a re-evaluated elite, is recorded
+        // once more
+        if errors.len().is_multiple_of(recorder.cases) {
+            let fitness =
+                errors[errors.len() - recorder.cases..].iter().sum::<f64>() / recorder.cases as f64;
+            recorder.record(Individual {
+                chromosome: self.chromosome.clone(),
+                // exploded velocities produce nan
+                fitness: if fitness.is_nan() {
+                    f64::INFINITY
+                } else {
+                    fitness
+                },
+                expression: self.phenotype.to_string(),
+                size: self.phenotype.size(),
+            });
+        }
+
+        output
+    }
+
+    fn generate(chromosome: &[u8]) -> Self {
+        Self {
+            chromosome: chromosome.to_vec(),
+            phenotype: G::generate(chromosome),
+            errors: Mutex::default(),
+            recorder: std::marker::PhantomData,
+        }
+    }
+}
+
+fn best(population: &[Individual]) -> &Individual {
+    population
+        .iter()
+        .min_by(|x, y| x.fitness.total_cmp(&y.fitness))
+        .unwrap()
+}
+
+fn summarise(population: &[Individual]) -> Generation {
+    let n = population.len() as f64;
+    let finite = population
+        .iter()
+        .map(|i| i.fitness)
+        .filter(|f| f.is_finite())
+        .collect::<Vec<_>>();
+    let best = best(population);
+
+    Generation {
+        best: best.fitness,
+        mean: if finite.is_empty() {
+            f64::INFINITY
+        } else {
+            finite.iter().sum::<f64>() / finite.len() as f64
+        },
+        worst: population
+            .iter()
+            .map(|i| i.fitness)
+            .max_by(|x, y| x.total_cmp(y))
+            .unwrap(),
+        genotype_diversity: population
+            .iter()
+            .map(|i| &i.chromosome)
+            .collect::<HashSet<_>>()
+            .len() as f64
+            / n,
+        phenotype_diversity: population
+            .iter()
+            .map(|i| &i.expression)
+            .collect::<HashSet<_>>()
+            .len() as f64
+            / n,
+        size: population.iter().map(|i| i.size as f64).sum::<f64>() / n,
+        expression: best.expression.clone(),
+    }
+}
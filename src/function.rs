@@ -1,26 +1,486 @@
+use crate::baseline::gaussian;
+use crate::{bbob, cec, custom, plugin};
 use mikes_pso::{bounds::Bound, vector::Vector};
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::cell::Cell;
 use std::f64::consts::{E, PI};
+use std::sync::{Arc, Mutex};
+
+/// Fitness of a position.
+pub type Fitness<const SIZE: usize> = Box<dyn Fn(&Vector<SIZE>) -> f64 + Send>;
+
+/// Fitness of every position of a swarm.
+pub type Batch<const SIZE: usize> = Box<dyn Fn(&[Vector<SIZE>]) -> Vec<f64> + Send>;
 
 pub struct Function<const SIZE: usize> {
-    pub func: Box<dyn Fn(&Vector<SIZE>) -> f64 + Send>,
+    pub name: String,
+    pub func: Fitness<SIZE>,
+    /// evaluates every position at once, giving the same fitness as `func`,
+    /// for functions which are faster in batches such as vectorised or
+    /// external ones
+    pub batch: Option<Batch<SIZE>>,
+    /// the function without noise for noisy functions, whose `func` is what
+    /// the optimisers see
+    pub truth: Option<Fitness<SIZE>>,
     pub minima: f64,
+    /// every known location of the minima, empty when unknown
+    pub optima: Vec<Vector<SIZE>>,
     pub bounds: Vec<Bound>,
+    /// natural dimension of functions only defined at a fixed dimension,
+    /// `None` when the function scales to any dimension
+    pub dimension: Option<usize>,
+    /// constraints beyond the bounds, empty for functions which are only
+    /// bound constrained
+    pub constraints: Vec<Constraint<SIZE>>,
+}
+
+/// Largest `|h(x)|` at which an equality constraint counts as satisfied.
+pub const TOLERANCE: f64 = 1e-4;
+
+/// A constraint every solution of a constrained function must satisfy.
+pub enum Constraint<const SIZE: usize> {
+    /// satisfied when `g(x) <= 0`
+    Inequality(Box<dyn Fn(&Vector<SIZE>) -> f64 + Send>),
+    /// satisfied when `|h(x)| <= TOLERANCE`
+    Equality(Box<dyn Fn(&Vector<SIZE>) -> f64 + Send>),
+}
+
+impl<const SIZE: usize> Constraint<SIZE> {
+    /// Amount by which `coords` breaks the constraint, zero when satisfied.
+    pub fn violation(&self, coords: &Vector<SIZE>) -> f64 {
+        match self {
+            Constraint::Inequality(g) => g(coords).max(0.0),
+            Constraint::Equality(h) => (h(coords).abs() - TOLERANCE).max(0.0),
+        }
+    }
+
+    fn remap(self, map: impl Fn(&Vector<SIZE>) -> Vector<SIZE> + Send + 'static) -> Self {
+        match self {
+            Constraint::Inequality(g) => {
+                Constraint::Inequality(Box::new(move |coords: &Vector<SIZE>| g(&map(coords))))
+            }
+            Constraint::Equality(h) => {
+                Constraint::Equality(Box::new(move |coords: &Vector<SIZE>| h(&map(coords))))
+            }
+        }
+    }
+}
+
+/// Wraps an objective function, counting how many times it is evaluated.
+pub struct Counted<F> {
+    func: F,
+    evaluations: Cell<usize>,
+}
+
+impl<F> Counted<F> {
+    pub fn new(func: F) -> Self {
+        Self {
+            func,
+            evaluations: Cell::new(0),
+        }
+    }
+
+    /// Evaluates every position in a single call, counting each of them.
+    pub fn evaluate<const SIZE: usize>(&self, positions: &[Vector<SIZE>]) -> Vec<f64>
+    where
+        F: Fn(&[Vector<SIZE>]) -> Vec<f64>,
+    {
+        self.evaluations
+            .set(self.evaluations.get() + positions.len());
+        (self.func)(positions)
+    }
+
+    pub fn evaluations(&self) -> usize {
+        self.evaluations.get()
+    }
+}
+
+impl<const SIZE: usize> Function<SIZE> {
+    /// Euclidean distance from `coords` to the nearest known optimum.
+    pub fn distance(&self, coords: &Vector<SIZE>) -> Option<f64> {
+        self.optima
+            .iter()
+            .map(|optimum| {
+                (0..SIZE)
+                    .map(|d| (coords[d] - optimum[d]).powi(2))
+                    .sum::<f64>()
+                    .sqrt()
+            })
+            .min_by(|x, y| x.total_cmp(y))
+    }
+
+    /// Length of the diagonal of the bounds.
+    pub fn diagonal(&self) -> f64 {
+        self.bounds
+            .iter()
+            .map(|bound| (bound.upper() - bound.lower()).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Whether the function is defined at `SIZE` dimensions.
+    pub fn fits(&self) -> bool {
+        self.dimension.is_none_or(|dimension| dimension == SIZE)
+    }
+
+    /// Total amount by which `coords` breaks the constraints, zero when it is
+    /// feasible.
+    pub fn violation(&self, coords: &Vector<SIZE>) -> f64 {
+        self.constraints
+            .iter()
+            .map(|constraint| constraint.violation(coords))
+            .sum()
+    }
+
+    /// Fitness of every position, in a single call when the function has a
+    /// batch evaluation.
+    pub fn evaluate_batch(&self, positions: &[Vector<SIZE>]) -> Vec<f64> {
+        match &self.batch {
+            Some(batch) => batch(positions),
+            None => positions.iter().map(|coords| (self.func)(coords)).collect(),
+        }
+    }
+
+    /// Fitness of `coords` without any noise.
+    pub fn true_fitness(&self, coords: &Vector<SIZE>) -> f64 {
+        match &self.truth {
+            Some(truth) => truth(coords),
+            None => (self.func)(coords),
+        }
+    }
+
+    /// Evaluates the function, its batch evaluation, its noiseless truth and
+    /// its constraints at `map(x)`.
+    fn remap(
+        self,
+        name: String,
+        map: impl Fn(&Vector<SIZE>) -> Vector<SIZE> + Clone + Send + 'static,
+    ) -> Self {
+        let func = self.func;
+        let batch = self.batch.map(|batch| {
+            let map = map.clone();
+            Box::new(move |positions: &[Vector<SIZE>]| {
+                batch(&positions.iter().map(&map).collect::<Vec<_>>())
+            }) as Batch<SIZE>
+        });
+        let truth = self.truth.map(|truth| {
+            let map = map.clone();
+            Box::new(move |coords: &Vector<SIZE>| truth(&map(coords))) as Fitness<SIZE>
+        });
+        let constraints = self
+            .constraints
+            .into_iter()
+            .map(|constraint| constraint.remap(map.clone()))
+            .collect();
+
+        Self {
+            name,
+            func: Box::new(move |coords: &Vector<SIZE>| func(&map(coords))),
+            batch,
+            truth,
+            constraints,
+            ..self
+        }
+    }
+
+    /// Moves the optimum by a random offset of up to 80% of the half range of
+    /// every bound, evaluating `f(x - o)` within the same bounds. The offset
+    /// is limited so that every known optimum stays within the bounds, as
+    /// those of many functions are already off centre.
+    pub fn shifted(self, rng: &mut impl Rng) -> Self {
+        let offset: [f64; SIZE] = std::array::from_fn(|d| {
+            let (lower, upper) = (self.bounds[d].lower(), self.bounds[d].upper());
+            let half = (upper - lower) / 2.0;
+            let (low, high) = self
+                .optima
+                .iter()
+                .fold((-0.8 * half, 0.8 * half), |(low, high), optimum| {
+                    (low.max(lower - optimum[d]), high.min(upper - optimum[d]))
+                });
+            if low <= high {
+                rng.gen_range(low..=high)
+            } else {
+                0.0
+            }
+        });
+        let optima: Vec<Vector<SIZE>> = self
+            .optima
+            .iter()
+            .map(|optimum| Vector::from(std::array::from_fn(|d| optimum[d] + offset[d])))
+            .collect();
+        let name = format!("shifted {}", self.name);
+
+        Self { optima, ..self }.remap(name, move |coords| {
+            Vector::from(std::array::from_fn(|d| coords[d] - offset[d]))
+        })
+    }
+
+    /// Rotates the function about the origin by a random orthogonal matrix,
+    /// evaluating `f(M x)` within the same bounds.
+    pub fn rotated(self, rng: &mut impl Rng) -> Self {
+        let rotation = cec::rotation(rng, SIZE);
+        // `M x = x*` is solved by `x = M^T x*` as `M` is orthogonal
+        let optima: Vec<Vector<SIZE>> = self
+            .optima
+            .iter()
+            .map(|optimum| {
+                Vector::from(std::array::from_fn(|d| {
+                    (0..SIZE).map(|k| rotation[k][d] * optimum[k]).sum()
+                }))
+            })
+            .collect();
+        let name = format!("rotated {}", self.name);
+
+        Self { optima, ..self }.remap(name, move |coords| {
+            Vector::from(std::array::from_fn(|d| {
+                rotation[d]
+                    .iter()
+                    .zip(coords.iter())
+                    .map(|(m, x)| m * x)
+                    .sum()
+            }))
+        })
+    }
+
+    /// Stretches every dimension by a random factor between 0.1 and 10,
+    /// evaluating `f(s x)` within bounds divided by `s` so that the search
+    /// space still covers the original one.
+    pub fn scaled(self, rng: &mut impl Rng) -> Self {
+        let scale: [f64; SIZE] = std::array::from_fn(|_| 10.0_f64.powf(rng.gen_range(-1.0..=1.0)));
+        let bounds = self
+            .bounds
+            .iter()
+            .zip(scale)
+            .map(|(bound, s)| Bound::from((bound.lower() / s, bound.upper() / s)))
+            .collect();
+        let optima: Vec<Vector<SIZE>> = self
+            .optima
+            .iter()
+            .map(|optimum| Vector::from(std::array::from_fn(|d| optimum[d] / scale[d])))
+            .collect();
+        let name = format!("scaled {}", self.name);
+
+        Self {
+            optima,
+            bounds,
+            ..self
+        }
+        .remap(name, move |coords| {
+            Vector::from(std::array::from_fn(|d| scale[d] * coords[d]))
+        })
+    }
+
+    /// Adds noise drawn from a generator seeded by `seed` to every
+    /// evaluation, keeping the noiseless function as the truth. Noise added
+    /// to an already noisy function is added on top of its noise.
+    ///
+    /// The generator is shared by every run on the function, so the noise a
+    /// run sees depends on the runs evaluated before or alongside it and on
+    /// their random initial swarms. The seed fixes the stream of noise, not
+    /// the results of an experiment.
+    pub fn noisy(self, noise: Noise, seed: u64) -> Self {
+        // shared with the batch evaluation so that both draw from the same
+        // stream of noise
+        let rng = Arc::new(Mutex::new(StdRng::seed_from_u64(seed)));
+        let func = Arc::new(Mutex::new(self.func));
+        let truth = match self.truth {
+            Some(truth) => truth,
+            None => {
+                let func = Arc::clone(&func);
+                Box::new(move |coords: &Vector<SIZE>| (func.lock().unwrap())(coords))
+            }
+        };
+        let batch = self.batch.map(|batch| {
+            let rng = Arc::clone(&rng);
+            Box::new(move |positions: &[Vector<SIZE>]| {
+                let rng = &mut *rng.lock().unwrap();
+                batch(positions)
+                    .into_iter()
+                    .map(|fitness| noise.apply(fitness, rng))
+                    .collect()
+            }) as Batch<SIZE>
+        });
+
+        Self {
+            name: format!("noisy {}", self.name),
+            func: Box::new(move |coords: &Vector<SIZE>| {
+                let fitness = (func.lock().unwrap())(coords);
+                noise.apply(fitness, &mut *rng.lock().unwrap())
+            }),
+            batch,
+            truth: Some(truth),
+            ..self
+        }
+    }
+}
+
+/// How noise is combined with the fitness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combination {
+    /// `f + level r`
+    Additive,
+    /// `f (1 + level r)`
+    Multiplicative,
+}
+
+/// Distribution of the random number `r` of the noise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distribution {
+    /// standard normal
+    Gaussian,
+    /// uniform in `[0, 1)`
+    Uniform,
+}
+
+/// Noise of a stochastic benchmark.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Noise {
+    pub combination: Combination,
+    pub distribution: Distribution,
+    pub level: f64,
+}
+
+impl Noise {
+    fn apply(&self, fitness: f64, rng: &mut impl Rng) -> f64 {
+        let r = match self.distribution {
+            Distribution::Gaussian => gaussian(rng),
+            Distribution::Uniform => rng.gen_range(0.0..1.0),
+        };
+
+        match self.combination {
+            Combination::Additive => fitness + self.level * r,
+            Combination::Multiplicative => fitness * (1.0 + self.level * r),
+        }
+    }
+}
+
+/// Random transformations applied to every function of a suite, each
+/// function drawing them from its own generator seeded by `seed` and its
+/// index.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Transform {
+    pub shift: bool,
+    pub rotate: bool,
+    pub scale: bool,
+    /// noise added after every other transformation
+    pub noise: Option<Noise>,
+    pub seed: u64,
+}
+
+impl Transform {
+    /// Applies the shift, then the rotation, then the scaling and then the
+    /// noise to `function`, the `i`th function of its suite.
+    pub fn apply<const SIZE: usize>(
+        &self,
+        i: usize,
+        mut function: Function<SIZE>,
+    ) -> Function<SIZE> {
+        let mut rng = StdRng::seed_from_u64(self.seed.wrapping_add(i as u64));
+        if self.shift {
+            function = function.shifted(&mut rng);
+        }
+        if self.rotate {
+            function = function.rotated(&mut rng);
+        }
+        if self.scale {
+            function = function.scaled(&mut rng);
+        }
+        if let Some(noise) = self.noise {
+            function = function.noisy(noise, rng.gen());
+        }
+
+        function
+    }
+}
+
+/// Benchmark suite the experiments are run on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Suite {
+    /// the 17 unshifted and unrotated functions of [`functions`]
+    Classic,
+    /// the shifted, rotated, hybrid and composition functions of
+    /// [`cec::functions`] generated from the seed
+    Cec(u64),
+    /// the 24 noiseless functions of [`bbob::functions`] of the instance
+    Bbob(usize),
+    /// the user-defined functions of [`custom::functions`] declared in the
+    /// file at the path
+    Custom(String),
+    /// the functions of [`plugin::functions`] served by the external program
+    /// run by the command
+    Plugin(String),
+}
+
+/// Every function of the suite with the transformations applied.
+pub fn suite<const SIZE: usize>(suite: &Suite, transform: Transform) -> Vec<Function<SIZE>> {
+    let functions: Vec<_> = match suite {
+        Suite::Classic => functions().into(),
+        Suite::Cec(seed) => cec::functions(*seed),
+        Suite::Bbob(instance) => bbob::functions(*instance),
+        Suite::Custom(path) => custom::functions(path),
+        Suite::Plugin(command) => plugin::functions(command),
+    };
+
+    functions
+        .into_iter()
+        .enumerate()
+        .map(|(i, function)| transform.apply(i, function))
+        .collect()
+}
+
+/// The point with the given leading coordinates and every other coordinate
+/// zero.
+pub fn point<const SIZE: usize>(coords: &[f64]) -> Vector<SIZE> {
+    Vector::from(std::array::from_fn(|d| {
+        coords.get(d).copied().unwrap_or(0.0)
+    }))
+}
+
+/// Number of positions summed side by side by `separable`.
+const LANES: usize = 8;
+
+/// Batch evaluation of the separable function `sum term(d, x[d])`, which adds
+/// the terms of `LANES` positions side by side one dimension at a time so
+/// that it vectorises, while adding the terms of every position in the same
+/// order as summing them one position at a time.
+fn separable<const SIZE: usize>(term: fn(usize, f64) -> f64) -> Option<Batch<SIZE>> {
+    Some(Box::new(move |positions: &[Vector<SIZE>]| {
+        positions
+            .chunks(LANES)
+            .flat_map(|chunk| {
+                // like the sum of an iterator
+                let mut sums = [-0.0; LANES];
+                for d in 0..SIZE {
+                    for (sum, coords) in sums.iter_mut().zip(chunk) {
+                        *sum += term(d, coords[d]);
+                    }
+                }
+                sums.into_iter().take(chunk.len())
+            })
+            .collect()
+    }))
 }
 
 pub fn functions<const SIZE: usize>() -> [Function<SIZE>; 17] {
     [
         // 1
         Function {
+            name: "matyas".to_string(),
             func: Box::new(|coords: &Vector<SIZE>| {
                 0.26 * (coords[0] * coords[0] + coords[1] * coords[1])
                     - 0.48 * coords[0] * coords[1]
             }),
+            batch: None,
+            truth: None,
             minima: 0.0,
+            optima: vec![point(&[])],
             bounds: vec![Bound::from((-10.0, 10.0)); SIZE],
+            dimension: Some(2),
+            constraints: Vec::new(),
         },
         // 2
         Function {
+            name: "six-hump camel".to_string(),
             func: Box::new(|coords: &Vector<SIZE>| {
                 4.0 * coords[0] * coords[0] - 2.1 * coords[0] * coords[0] * coords[0] * coords[0]
                     + (coords[0] * coords[0] * coords[0] * coords[0] * coords[0] * coords[0]) / 3.0
@@ -28,17 +488,29 @@ pub fn functions<const SIZE: usize>() -> [Function<SIZE>; 17] {
                     - 4.0 * coords[1] * coords[1]
                     + 4.0 * coords[1] * coords[1] * coords[1] * coords[1]
             }),
+            batch: None,
+            truth: None,
             minima: -1.0316,
+            optima: vec![point(&[0.0898, -0.7126]), point(&[-0.0898, 0.7126])],
             bounds: vec![Bound::from((-5.0, 5.0)); SIZE],
+            dimension: Some(2),
+            constraints: Vec::new(),
         },
         // 3 - sphere
         Function {
+            name: "sphere".to_string(),
             func: Box::new(|coords: &Vector<SIZE>| coords.iter().map(|x| x * x).sum()),
+            batch: separable(|_, x| x * x),
+            truth: None,
             minima: 0.0,
+            optima: vec![point(&[])],
             bounds: vec![Bound::from((-100.0, 100.0)); SIZE],
+            dimension: None,
+            constraints: Vec::new(),
         },
         // 4
         Function {
+            name: "schwefel 2.22".to_string(),
             func: Box::new(|coords: &Vector<SIZE>| {
                 coords.iter().map(|x| x.abs()).sum::<f64>()
                     + coords
@@ -47,11 +519,17 @@ pub fn functions<const SIZE: usize>() -> [Function<SIZE>; 17] {
                         .reduce(|acc, e| acc * e)
                         .unwrap()
             }),
+            batch: None,
+            truth: None,
             minima: 0.0,
+            optima: vec![point(&[])],
             bounds: vec![Bound::from((-10.0, 10.0)); SIZE],
+            dimension: None,
+            constraints: Vec::new(),
         },
         // 5 - schwefels
         Function {
+            name: "schwefel 1.2".to_string(),
             func: Box::new(|coords: &Vector<SIZE>| {
                 let mut x = 0.0;
                 for i in 0..coords.size() {
@@ -64,11 +542,17 @@ pub fn functions<const SIZE: usize>() -> [Function<SIZE>; 17] {
 
                 x
             }),
+            batch: None,
+            truth: None,
             minima: 0.0,
+            optima: vec![point(&[])],
             bounds: vec![Bound::from((-100.0, 100.0)); SIZE],
+            dimension: None,
+            constraints: Vec::new(),
         },
         // 6
         Function {
+            name: "schwefel 2.21".to_string(),
             func: Box::new(|coords: &Vector<SIZE>| {
                 coords
                     .iter()
@@ -76,11 +560,17 @@ pub fn functions<const SIZE: usize>() -> [Function<SIZE>; 17] {
                     .max_by(|x, y| x.total_cmp(y))
                     .unwrap()
             }),
+            batch: None,
+            truth: None,
             minima: 0.0,
+            optima: vec![point(&[])],
             bounds: vec![Bound::from((-100.0, 100.0)); SIZE],
+            dimension: None,
+            constraints: Vec::new(),
         },
         // 7
         Function {
+            name: "sum squares".to_string(),
             func: Box::new(|coords: &Vector<SIZE>| {
                 coords
                     .iter()
@@ -88,11 +578,17 @@ pub fn functions<const SIZE: usize>() -> [Function<SIZE>; 17] {
                     .map(|(i, x)| (i + 1) as f64 * x * x)
                     .sum()
             }),
+            batch: separable(|i, x| (i + 1) as f64 * x * x),
+            truth: None,
             minima: 0.0,
+            optima: vec![point(&[])],
             bounds: vec![Bound::from((-10.0, 10.0)); SIZE],
+            dimension: None,
+            constraints: Vec::new(),
         },
         // 8
         Function {
+            name: "quartic".to_string(),
             func: Box::new(|coords: &Vector<SIZE>| {
                 coords
                     .iter()
@@ -100,11 +596,17 @@ pub fn functions<const SIZE: usize>() -> [Function<SIZE>; 17] {
                     .map(|(i, x)| (i + 1) as f64 * x * x * x * x)
                     .sum()
             }),
+            batch: separable(|i, x| (i + 1) as f64 * x * x * x * x),
+            truth: None,
             minima: 0.0,
+            optima: vec![point(&[])],
             bounds: vec![Bound::from((-1.28, 1.28)); SIZE],
+            dimension: None,
+            constraints: Vec::new(),
         },
         // 9
         Function {
+            name: "sum of different powers".to_string(),
             func: Box::new(|coords: &Vector<SIZE>| {
                 coords
                     .iter()
@@ -112,11 +614,17 @@ pub fn functions<const SIZE: usize>() -> [Function<SIZE>; 17] {
                     .map(|(i, x)| x.abs().powi(i as i32 + 2))
                     .sum()
             }),
+            batch: None,
+            truth: None,
             minima: 0.0,
+            optima: vec![point(&[])],
             bounds: vec![Bound::from((-1.0, 1.0)); SIZE],
+            dimension: None,
+            constraints: Vec::new(),
         },
         // 10
         Function {
+            name: "high conditioned elliptic".to_string(),
             func: Box::new(|coords: &Vector<SIZE>| {
                 coords
                     .iter()
@@ -126,43 +634,74 @@ pub fn functions<const SIZE: usize>() -> [Function<SIZE>; 17] {
                     })
                     .sum()
             }),
+            batch: None,
+            truth: None,
             minima: 0.0,
+            optima: vec![point(&[])],
             bounds: vec![Bound::from((-100.0, 100.0)); SIZE],
+            dimension: None,
+            constraints: Vec::new(),
         },
         // 11
         Function {
+            name: "step".to_string(),
             func: Box::new(|coords: &Vector<SIZE>| {
                 coords.iter().map(|x| (x + 0.5).floor().powf(2.0)).sum()
             }),
+            batch: separable(|_, x| (x + 0.5).floor().powf(2.0)),
+            truth: None,
             minima: 0.0,
+            optima: vec![point(&[])],
             bounds: vec![Bound::from((-1.28, 1.28)); SIZE],
+            dimension: None,
+            constraints: Vec::new(),
         },
         // 12
         Function {
+            name: "quartic".to_string(),
             func: Box::new(|coords: &Vector<SIZE>| {
                 coords
                     .iter()
                     .enumerate()
                     .map(|(i, x)| (i + 1) as f64 * x * x * x * x)
                     .sum::<f64>()
-                    + rand::thread_rng().gen_range(0.0..1.0)
             }),
+            batch: separable(|i, x| (i + 1) as f64 * x * x * x * x),
+            truth: None,
             minima: 0.0,
+            optima: vec![point(&[])],
             bounds: vec![Bound::from((-1.28, 1.28)); SIZE],
-        },
+            dimension: None,
+            constraints: Vec::new(),
+        }
+        .noisy(
+            Noise {
+                combination: Combination::Additive,
+                distribution: Distribution::Uniform,
+                level: 1.0,
+            },
+            12,
+        ),
         // 13
         Function {
+            name: "rastrigin".to_string(),
             func: Box::new(|coords: &Vector<SIZE>| {
                 coords
                     .iter()
                     .map(|x| x * x - 10.0 * (2.0 * PI * x).cos() + 10.0)
                     .sum()
             }),
+            batch: separable(|_, x| x * x - 10.0 * (2.0 * PI * x).cos() + 10.0),
+            truth: None,
             minima: 0.0,
+            optima: vec![point(&[])],
             bounds: vec![Bound::from((-5.12, 5.12)); SIZE],
+            dimension: None,
+            constraints: Vec::new(),
         },
         // 14 - ackley
         Function {
+            name: "ackley".to_string(),
             func: Box::new(|coords: &Vector<SIZE>| {
                 -20.0
                     * E.powf(
@@ -170,20 +709,23 @@ pub fn functions<const SIZE: usize>() -> [Function<SIZE>; 17] {
                             .sqrt(),
                     )
                     - E.powf(
-                        coords
-                            .iter()
-                            .map(|x| ((2.0 * PI * x) / coords.size() as f64).cos())
-                            .sum::<f64>()
+                        coords.iter().map(|x| (2.0 * PI * x).cos()).sum::<f64>()
                             / coords.size() as f64,
                     )
                     + 20.0
                     + E
             }),
+            batch: None,
+            truth: None,
             minima: 0.0,
+            optima: vec![point(&[])],
             bounds: vec![Bound::from((-32.0, 32.0)); SIZE],
+            dimension: None,
+            constraints: Vec::new(),
         },
         // 15
         Function {
+            name: "griewank".to_string(),
             func: Box::new(|coords: &Vector<SIZE>| {
                 (coords.iter().map(|x| x * x).sum::<f64>()) / 4000.0
                     - coords
@@ -194,11 +736,17 @@ pub fn functions<const SIZE: usize>() -> [Function<SIZE>; 17] {
                         .unwrap()
                     + 1.0
             }),
+            batch: None,
+            truth: None,
             minima: 0.0,
+            optima: vec![point(&[])],
             bounds: vec![Bound::from((-600.0, 600.0)); SIZE],
+            dimension: None,
+            constraints: Vec::new(),
         },
         // 16
         Function {
+            name: "schaffer f6".to_string(),
             func: Box::new(|coords: &Vector<SIZE>| {
                 0.5 + (coords
                     .iter()
@@ -210,8 +758,13 @@ pub fn functions<const SIZE: usize>() -> [Function<SIZE>; 17] {
                     - 0.5)
                     / (1.0 + 0.001 * coords.iter().map(|x| x * x).sum::<f64>()).powf(2.0)
             }),
+            batch: None,
+            truth: None,
             minima: 0.0,
+            optima: vec![point(&[])],
             bounds: vec![Bound::from((-100.0, 100.0)); SIZE],
+            dimension: None,
+            constraints: Vec::new(),
         },
         // 17
         // Function {
@@ -227,11 +780,100 @@ pub fn functions<const SIZE: usize>() -> [Function<SIZE>; 17] {
         // },
         // 18
         Function {
+            name: "alpine".to_string(),
             func: Box::new(|coords: &Vector<SIZE>| {
                 coords.iter().map(|x| (x * x.sin() + 0.1 * x).abs()).sum()
             }),
+            batch: separable(|_, x| (x * x.sin() + 0.1 * x).abs()),
+            truth: None,
             minima: 0.0,
+            // every coordinate solving `sin x = -0.1` is also optimal, only the
+            // origin is listed
+            optima: vec![point(&[])],
             bounds: vec![Bound::from((-10.0, 10.0)); SIZE],
+            dimension: None,
+            constraints: Vec::new(),
         },
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Dimension which leaves a partial chunk of lanes.
+    const D: usize = 7;
+
+    /// Positions drawn uniformly within the bounds of `function`, more than
+    /// `LANES` of them so that the last chunk is partial.
+    fn positions(function: &Function<D>, rng: &mut impl Rng) -> Vec<Vector<D>> {
+        (0..2 * LANES + 3)
+            .map(|_| {
+                Vector::from(std::array::from_fn(|d| {
+                    let bound = &function.bounds[d];
+                    rng.gen_range(bound.lower()..=bound.upper())
+                }))
+            })
+            .collect()
+    }
+
+    /// Checks the batch evaluation of every batched function, transformed by
+    /// `transform`, against the fitness of its copy evaluated one position at
+    /// a time.
+    fn check(transform: impl Fn(Function<D>) -> Function<D>) {
+        let mut rng = StdRng::seed_from_u64(0);
+        let batched = functions::<D>().into_iter().map(&transform);
+        let single = functions::<D>().into_iter().map(&transform);
+        let mut checked = 0;
+        for (batched, single) in batched.zip(single) {
+            if batched.batch.is_none() {
+                continue;
+            }
+            let positions = positions(&single, &mut rng);
+            assert_eq!(
+                batched.evaluate_batch(&positions),
+                positions
+                    .iter()
+                    .map(|x| (single.func)(x))
+                    .collect::<Vec<_>>(),
+                "{}",
+                batched.name
+            );
+            checked += 1;
+        }
+        assert_eq!(checked, 7);
+    }
+
+    #[test]
+    fn batch() {
+        check(|function| function);
+    }
+
+    #[test]
+    fn transformed_batch() {
+        check(|function| function.shifted(&mut StdRng::seed_from_u64(1)));
+        check(|function| function.rotated(&mut StdRng::seed_from_u64(2)));
+        check(|function| {
+            let mut rng = StdRng::seed_from_u64(3);
+            function.shifted(&mut rng).rotated(&mut rng)
+        });
+    }
+
+    #[test]
+    fn noisy_batch() {
+        for distribution in [Distribution::Gaussian, Distribution::Uniform] {
+            let noise = Noise {
+                combination: Combination::Additive,
+                distribution,
+                level: 1.0,
+            };
+            // identically seeded copies draw the same noise
+            check(|function| function.noisy(noise, 4));
+            check(|function| {
+                function
+                    .rotated(&mut StdRng::seed_from_u64(5))
+                    .noisy(noise, 6)
+            });
+        }
+    }
+}
@@ -1,33 +1,81 @@
+mod baseline;
+mod bbob;
+mod binary;
+mod cec;
+mod coco;
+mod constrained;
+mod custom;
+mod dynamic;
+mod evolver;
 mod function;
+mod metrics;
+mod pareto;
+mod plot;
+mod plugin;
+mod realworld;
+mod report;
+mod strategy;
+mod swarm;
+mod topology;
 mod velocity;
 
-use function::Function;
-use mikes_ge::ge::GE;
+use dynamic::{Dynamic, Tracking};
+use evolver::{Evolver, Trace};
+use function::{Combination, Distribution, Function, Noise, Suite, Transform};
+use metrics::{Attempt, FixedTarget, Placement, EPSILONS};
 use mikes_ge::grammar::Grammar;
-use mikes_pso::particle::Particle;
-use mikes_pso::{bounds::Bound, pso::pso, vector::Vector};
+use pareto::{Pareto, Problem};
 use std::borrow::Borrow;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::Write;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
+use strategy::{Canonical, Random, VelocityStrategy};
+use swarm::{optimise, pso, Boundary, Handling, History, Movement, Settings};
 use threadpool::ThreadPool;
+use topology::Topology;
 use velocity::Velocity;
 
-fn run_all_functions() {
-    const SIZE: usize = 100;
-    let functions = function::functions::<SIZE>();
+/// Dimension of every experiment.
+const SIZE: usize = 100;
+
+/// Dimension the fixed-dimension functions are run at instead of `SIZE`.
+const FIXED: usize = 2;
+
+/// Default number of function evaluations per dimension every strategy is
+/// given, as in the cec benchmarks.
+const BUDGET: usize = 10000;
+
+/// Settings of the pso runs used as fitness evaluations while evolving, which
+/// are never given the evaluation budget of the experiment as it would make
+/// the evolution far too expensive.
+fn training(settings: Settings) -> Settings {
+    Settings {
+        evaluations: None,
+        evaluations_per_dimension: None,
+        diversity: false,
+        ..settings
+    }
+}
+
+fn run_all_functions(suite: Suite, transform: Transform, settings: Settings) {
+    let functions = function::suite::<SIZE>(&suite, transform);
+    let fixed = function::suite::<FIXED>(&suite, transform);
     //
-    let results = Arc::new(Mutex::new(Vec::with_capacity(17)));
+    let results = Arc::new(Mutex::new(Vec::with_capacity(functions.len())));
     let pool = ThreadPool::default();
 
     // unique solution
     println!("Starting Single Function Runs");
-    for (i, function) in functions.into_iter().enumerate() {
+    for (i, (function, fixed)) in functions.into_iter().zip(fixed).enumerate() {
         let results = Arc::clone(&results);
         pool.execute(move || {
             println!("Starting function: {i}");
-            let res = run_functions(function);
+            let res = if function.fits() {
+                run_functions(function, &format!("disposable{SIZE}_f{}", i + 1), settings)
+            } else {
+                run_functions(fixed, &format!("disposable{FIXED}_f{}", i + 1), settings)
+            };
             results.lock().unwrap().push((i, res));
             println!("Finished function: {i}");
         });
@@ -36,19 +84,22 @@ fn run_all_functions() {
     // general solution
     println!("Starting Multi Function Runs");
 
-    pool.execute(|| {
+    pool.execute(move || {
         let mut file = File::create("./results_copy/reusable100.csv").unwrap();
-        file.write(b"min, mean, std, time(s)\n").unwrap();
+        file.write_all(FunctionResult::HEADER).unwrap();
 
-        let functions = function::functions::<SIZE>();
+        let functions = function::suite::<SIZE>(&suite, transform);
+        let fixed = function::suite::<FIXED>(&suite, transform);
+        // fixed-dimension functions cannot be trained on at `SIZE`
         let train = functions
             .iter()
+            .filter(|function| function.fits())
             .map(|function| {
                 (
                     (
-                        &function.borrow().func
-                            as &Box<dyn for<'a> Fn(&'a Vector<SIZE>) -> f64 + Send>,
+                        function,
                         function.borrow().bounds.as_slice(),
+                        training(settings),
                     ),
                     function.borrow().minima,
                 )
@@ -58,114 +109,708 @@ fn run_all_functions() {
         let start = Instant::now();
         // println!("Run: {r}");
 
-        let mut ge =
-            GE::<(&Box<dyn Fn(&Vector<SIZE>) -> f64 + Send>, &[Bound]), f64, Velocity<SIZE>>::new(
-                100,
-                (0.7, 0.3, 0.0),
-                3,
-                10,
-                100,
-                5,
-                30,
-                &train,
-            );
-        let chromosome = ge.start();
+        let mut evolver =
+            Evolver::<Velocity<SIZE>>::new(100, (0.7, 0.3, 0.0), 3, 10, 100, 5, 30, &train);
+        let (chromosome, trace) = evolver.start();
         let end = start.elapsed();
+        write_trace(&format!("reusable{SIZE}"), &trace);
 
-        // creating the velocity equation
-        let velocity = Velocity::generate(&chromosome);
+        // creating the velocity equation, which is the same expression at
+        // every dimension
+        let velocity = Velocity::<SIZE>::generate(&chromosome);
+        let fixed_velocity = Velocity::<FIXED>::generate(&chromosome);
         // dbg!(&velocity);
-        let func = |current: &_, best: &_| velocity.runner(current, best);
 
         // running the pso
-        for function in &functions {
-            let mut results = Vec::with_capacity(30);
-            for _ in 0..30 {
-                let function = function.borrow();
-                let particle = pso(100, 100, &function.bounds, func, &function.func);
-                let minima = (function.func)(&particle.coordinates());
-                results.push(minima)
-            }
-            let mut min = f64::MAX;
-            for result in &results {
-                if result < &min {
-                    min = *result
-                }
-            }
-            let mean = results.iter().sum::<f64>() / 30.0;
-            let std = (results.iter().map(|r| (r - mean) * (r - mean)).sum::<f64>()
-                / results.len() as f64)
-                .sqrt();
-            file.write(format!("{min}, {mean}, {std}, {:.4}\n", end.as_secs_f64()).as_bytes())
-                .unwrap();
+        for (i, (function, fixed)) in functions.iter().zip(&fixed).enumerate() {
+            let result = if function.fits() {
+                run_strategy(
+                    &velocity,
+                    &[function],
+                    &format!("reusable{SIZE}_f{}", i + 1),
+                    settings,
+                )
+            } else {
+                run_strategy(
+                    &fixed_velocity,
+                    &[fixed],
+                    &format!("reusable{FIXED}_f{}", i + 1),
+                    settings,
+                )
+            };
+            let result = FunctionResult {
+                time: end,
+                ..result
+            };
+            file.write_all(result.to_csv().as_bytes()).unwrap();
         }
     });
 
     pool.join();
 
     let mut file = File::create("./results_copy/disposable100.csv").unwrap();
-    file.write(b"min, mean, std, time(s)\n").unwrap();
+    file.write_all(FunctionResult::HEADER).unwrap();
     results
         .lock()
         .unwrap()
         .sort_unstable_by(|(i, _), (j, _)| i.cmp(j));
 
     for (_, result) in results.lock().unwrap().iter() {
-        file.write(result.to_csv().as_bytes()).unwrap();
+        file.write_all(result.to_csv().as_bytes()).unwrap();
     }
 
     // println!("Starting Canoncial PSO");
     // println!("Starting Single Function Runs");
-    // for function in &functions {
-    //     let res = run_canonical_pso(&[function]);
+    // for (i, function) in functions.iter().enumerate() {
+    //     let res = run_strategy(&Canonical::default(), &[function], &format!("canonical{SIZE}_f{}", i + 1), settings);
     //     file.write(res.to_csv().as_bytes()).unwrap();
     // }
 
     // general solution
     // println!("Starting Multi Function Runs");
-    // let res = run_canonical_pso(&functions);
+    // let res = run_strategy(&Canonical::default(), &functions, &format!("canonical{SIZE}"), settings);
     // file.write(res.to_csv().as_bytes()).unwrap();
 }
 
+/// Runs every baseline whose name is in `names`, or all of them when no names
+/// are given.
+fn run_baselines(
+    names: Option<&str>,
+    canonical: Canonical,
+    suite: Suite,
+    transform: Transform,
+    settings: Settings,
+) {
+    let functions = function::suite::<SIZE>(&suite, transform);
+    let fixed = function::suite::<FIXED>(&suite, transform);
+    for (strategy, fixed_strategy) in baseline::baselines::<SIZE>(canonical)
+        .into_iter()
+        .zip(baseline::baselines::<FIXED>(canonical))
+    {
+        if names.is_none_or(|names| names.split(',').any(|name| name == strategy.name())) {
+            run_baseline(
+                (strategy.as_ref(), fixed_strategy.as_ref()),
+                (&functions, &fixed),
+                settings,
+            );
+        }
+    }
+}
+
+/// Runs a baseline strategy on every function separately, each at `SIZE`
+/// unless it is a fixed-dimension function which is run at `FIXED`, writing
+/// the results to `./results_copy/{name}{SIZE}.csv`.
+fn run_baseline(
+    (strategy, fixed_strategy): (&dyn VelocityStrategy<SIZE>, &dyn VelocityStrategy<FIXED>),
+    (functions, fixed): (&[Function<SIZE>], &[Function<FIXED>]),
+    settings: Settings,
+) {
+    let name = strategy.name();
+    let parameters = strategy
+        .parameters()
+        .iter()
+        .map(|(parameter, value)| format!("{parameter} = {value}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    println!("Starting {name} ({parameters})");
+
+    let mut file = File::create(format!("./results_copy/{name}{SIZE}.csv")).unwrap();
+    file.write_all(FunctionResult::HEADER).unwrap();
+    for (i, (function, fixed)) in functions.iter().zip(fixed).enumerate() {
+        let res = if function.fits() {
+            run_strategy(
+                strategy,
+                &[function],
+                &format!("{name}{SIZE}_f{}", i + 1),
+                settings,
+            )
+        } else {
+            run_strategy(
+                fixed_strategy,
+                &[fixed],
+                &format!("{name}{FIXED}_f{}", i + 1),
+                settings,
+            )
+        };
+        file.write_all(res.to_csv().as_bytes()).unwrap();
+    }
+}
+
+/// Runs every baseline whose name is in `names` (or all of them when no
+/// names are given) on every constrained problem under the constraint
+/// handling of the settings, writing the results to
+/// `./results_copy/constrained/{name}.csv` with the problems in order.
+fn run_constrained(names: Option<&str>, canonical: Canonical, settings: Settings) {
+    fs::create_dir_all("./results_copy/constrained").unwrap();
+
+    // every problem is run at its own dimension
+    let mut results = Vec::new();
+    macro_rules! run_at {
+        ($($dimension:literal),*) => {
+            $(run_at(
+                constrained::functions::<$dimension>(),
+                "constrained",
+                names,
+                canonical,
+                settings,
+                &mut results,
+            );)*
+        };
+    }
+    run_at!(2, 3, 4, 5, 7, 8, 10, 13, 20);
+    write_grouped("./results_copy/constrained", results);
+}
+
+/// Runs the selected baselines and, when `disposable` is selected, a
+/// velocity rule evolved on each problem, on every real-world problem at its
+/// own dimension, writing the results to `./results_copy/realworld/{name}.csv`
+/// with the problems in order.
+fn run_realworld(names: Option<&str>, canonical: Canonical, settings: Settings) {
+    fs::create_dir_all("./results_copy/realworld").unwrap();
+
+    let mut results = Vec::new();
+    run_at(
+        realworld::functions::<6>(),
+        "realworld",
+        names,
+        canonical,
+        settings,
+        &mut results,
+    );
+    run_at(
+        realworld::functions::<20>(),
+        "realworld",
+        names,
+        canonical,
+        settings,
+        &mut results,
+    );
+    run_at(
+        realworld::functions::<25>(),
+        "realworld",
+        names,
+        canonical,
+        settings,
+        &mut results,
+    );
+    run_at(
+        realworld::functions::<30>(),
+        "realworld",
+        names,
+        canonical,
+        settings,
+        &mut results,
+    );
+    if names.is_some_and(|names| names.split(',').any(|n| n == "disposable")) {
+        run_disposable_at(
+            realworld::functions::<6>(),
+            "realworld",
+            settings,
+            &mut results,
+        );
+        run_disposable_at(
+            realworld::functions::<20>(),
+            "realworld",
+            settings,
+            &mut results,
+        );
+        run_disposable_at(
+            realworld::functions::<25>(),
+            "realworld",
+            settings,
+            &mut results,
+        );
+        run_disposable_at(
+            realworld::functions::<30>(),
+            "realworld",
+            settings,
+            &mut results,
+        );
+    }
+    write_grouped("./results_copy/realworld", results);
+}
+
+/// Runs the baselines whose name is in `names` (or all of them when no names
+/// are given) on every function which fits `SIZE`, pushing the strategy name
+/// and index of the function alongside each result.
+fn run_at<const SIZE: usize>(
+    functions: Vec<Function<SIZE>>,
+    label: &str,
+    names: Option<&str>,
+    canonical: Canonical,
+    settings: Settings,
+    results: &mut Vec<(String, usize, FunctionResult)>,
+) {
+    for strategy in baseline::baselines::<SIZE>(canonical) {
+        let name = strategy.name();
+        if names.is_none_or(|names| names.split(',').any(|n| n == name)) {
+            for (i, function) in functions.iter().enumerate() {
+                if function.fits() {
+                    println!("Starting {name} on {}", function.name);
+                    let result = run_strategy(
+                        strategy.as_ref(),
+                        &[function],
+                        &format!("{label}_{name}_f{}", i + 1),
+                        settings,
+                    );
+                    results.push((name.clone(), i, result));
+                }
+            }
+        }
+    }
+}
+
+/// Evolves a velocity rule on each function which fits `SIZE`, pushing it
+/// like `run_at` under the name `disposable`.
+fn run_disposable_at<const SIZE: usize>(
+    functions: Vec<Function<SIZE>>,
+    label: &str,
+    settings: Settings,
+    results: &mut Vec<(String, usize, FunctionResult)>,
+) {
+    for (i, function) in functions.into_iter().enumerate() {
+        if function.fits() {
+            println!("Starting disposable on {}", function.name);
+            let result = run_functions(
+                function,
+                &format!("{label}_disposable_f{}", i + 1),
+                settings,
+            );
+            results.push(("disposable".to_string(), i, result));
+        }
+    }
+}
+
+/// Writes the results of every strategy to `{folder}/{name}.csv`, ordered by
+/// the index of the function.
+fn write_grouped(folder: &str, mut results: Vec<(String, usize, FunctionResult)>) {
+    results.sort_unstable_by(|(x, i, _), (y, j, _)| x.cmp(y).then(i.cmp(j)));
+    for chunk in results.chunk_by(|(x, _, _), (y, _, _)| x == y) {
+        let name = &chunk[0].0;
+        let mut file = File::create(format!("{folder}/{name}.csv")).unwrap();
+        file.write_all(FunctionResult::HEADER).unwrap();
+        for (_, _, result) in chunk {
+            file.write_all(result.to_csv().as_bytes()).unwrap();
+        }
+    }
+}
+
+/// Runs every baseline whose name is in `names` (or all of them when no
+/// names are given) once on every bbob instance, writing COCO data folders to
+/// `{output}/{name}`. The `reusable` strategy is a velocity rule evolved on
+/// the first instance of every bbob function.
+fn run_coco(
+    names: Option<&str>,
+    instances: &[usize],
+    canonical: Canonical,
+    settings: Settings,
+    output: &str,
+) {
+    let selected = |name: &str| names.is_none_or(|names| names.split(',').any(|n| n == name));
+    for strategy in baseline::baselines::<SIZE>(canonical) {
+        if selected(&strategy.name()) {
+            run_coco_strategy(strategy.as_ref(), "", instances, settings, output);
+        }
+    }
+
+    if selected("reusable") {
+        println!("Evolving reusable velocity on bbob");
+        let functions = bbob::functions::<SIZE>(1);
+        let train = functions
+            .iter()
+            .map(|function| {
+                (
+                    (function, function.bounds.as_slice(), training(settings)),
+                    function.minima,
+                )
+            })
+            .collect::<Vec<_>>();
+        let mut evolver =
+            Evolver::<Velocity<SIZE>>::new(100, (0.7, 0.3, 0.0), 3, 10, 100, 5, 30, &train);
+        let (chromosome, trace) = evolver.start();
+        write_trace(&format!("coco_reusable{SIZE}"), &trace);
+
+        let velocity = Velocity::<SIZE>::generate(&chromosome);
+        run_coco_strategy(&velocity, "reusable", instances, settings, output);
+    }
+}
+
+/// Runs a strategy once on every instance of every bbob function, writing the
+/// runs to `{output}/{name}` where the name defaults to the strategy's own.
+fn run_coco_strategy<const SIZE: usize>(
+    strategy: &(impl VelocityStrategy<SIZE> + ?Sized),
+    name: &str,
+    instances: &[usize],
+    settings: Settings,
+    output: &str,
+) {
+    let name = if name.is_empty() {
+        strategy.name()
+    } else {
+        name.to_string()
+    };
+    println!("Starting {name} on bbob");
+
+    let mut runs = (0..24)
+        .map(|_| Vec::with_capacity(instances.len()))
+        .collect::<Vec<_>>();
+    for &instance in instances {
+        for (f, function) in bbob::functions::<SIZE>(instance).iter().enumerate() {
+            let run = pso(&settings, &function.bounds, strategy, &function.func);
+            runs[f].push((
+                instance,
+                Attempt {
+                    minima: function.minima,
+                    improvements: run.history.improvements,
+                    evaluations: run.evaluations,
+                },
+            ));
+        }
+    }
+    for (f, runs) in runs.iter().enumerate() {
+        coco::write(output, &name, f + 1, SIZE, runs);
+    }
+}
+
+/// Runs every baseline whose name is in `names` (or all of them when no
+/// names are given) with the multi-objective pso on every zdt and dtlz
+/// problem, writing the results to `./results_copy/pareto/{name}{SIZE}.csv`.
+/// The `reusable` strategy is a velocity rule evolved on every problem at
+/// once.
+fn run_pareto(names: Option<&str>, canonical: Canonical, settings: Settings) {
+    fs::create_dir_all("./results_copy/pareto").unwrap();
+    let problems = pareto::problems::<SIZE>();
+    let selected = |name: &str| names.is_none_or(|names| names.split(',').any(|n| n == name));
+    for strategy in baseline::baselines::<SIZE>(canonical) {
+        if selected(&strategy.name()) {
+            println!("Starting {} on pareto", strategy.name());
+            run_pareto_strategy(strategy.as_ref(), &strategy.name(), &problems, settings);
+        }
+    }
+
+    if selected("reusable") {
+        println!("Evolving reusable velocity on pareto");
+        let train = problems
+            .iter()
+            .map(|problem| ((problem, training(settings)), 0.0))
+            .collect::<Vec<_>>();
+        let mut evolver =
+            Evolver::<Pareto<SIZE>>::new(100, (0.7, 0.3, 0.0), 3, 10, 100, 5, 30, &train);
+        let (chromosome, trace) = evolver.start();
+        write_trace(&format!("pareto_reusable{SIZE}"), &trace);
+
+        let velocity = Velocity::<SIZE>::generate(&chromosome);
+        run_pareto_strategy(&velocity, "reusable", &problems, settings);
+    }
+}
+
+/// Runs every baseline whose name is in `names` (or all of them when no
+/// names are given) in the binary pso on every binary benchmark, writing the
+/// results to `./results_copy/binary/{name}{SIZE}.csv`. The `reusable`
+/// strategy is a velocity rule evolved in the binary pso on every benchmark
+/// at once. Without a velocity clamp every velocity is clamped to `[-4, 4]`
+/// so that the sigmoid cannot saturate.
+fn run_binary(names: Option<&str>, canonical: Canonical, settings: Settings) {
+    fs::create_dir_all("./results_copy/binary").unwrap();
+    let functions = binary::functions::<SIZE>();
+    let settings = Settings {
+        movement: Movement::Binary,
+        vmax: settings.vmax.or(Some(4.0)),
+        ..settings
+    };
+    let selected = |name: &str| names.is_none_or(|names| names.split(',').any(|n| n == name));
+    for strategy in baseline::baselines::<SIZE>(canonical) {
+        if selected(&strategy.name()) {
+            println!("Starting {} on binary", strategy.name());
+            run_binary_strategy(strategy.as_ref(), &strategy.name(), &functions, settings);
+        }
+    }
+
+    if selected("reusable") {
+        println!("Evolving reusable velocity on binary");
+        let train = functions
+            .iter()
+            .map(|function| {
+                (
+                    (function, function.bounds.as_slice(), training(settings)),
+                    function.minima,
+                )
+            })
+            .collect::<Vec<_>>();
+        let mut evolver =
+            Evolver::<Velocity<SIZE>>::new(100, (0.7, 0.3, 0.0), 3, 10, 100, 5, 30, &train);
+        let (chromosome, trace) = evolver.start();
+        write_trace(&format!("binary_reusable{SIZE}"), &trace);
+
+        let velocity = Velocity::<SIZE>::generate(&chromosome);
+        run_binary_strategy(&velocity, "reusable", &functions, settings);
+    }
+}
+
+/// Runs a strategy on every binary benchmark separately, writing the results
+/// to `./results_copy/binary/{name}{SIZE}.csv`.
+fn run_binary_strategy(
+    strategy: &(impl VelocityStrategy<SIZE> + ?Sized),
+    name: &str,
+    functions: &[Function<SIZE>],
+    settings: Settings,
+) {
+    let mut file = File::create(format!("./results_copy/binary/{name}{SIZE}.csv")).unwrap();
+    file.write_all(FunctionResult::HEADER).unwrap();
+    for (i, function) in functions.iter().enumerate() {
+        let result = run_strategy(
+            strategy,
+            &[function],
+            &format!("binary_{name}{SIZE}_f{}", i + 1),
+            settings,
+        );
+        file.write_all(result.to_csv().as_bytes()).unwrap();
+    }
+}
+
+/// Runs every baseline whose name is in `names` (or all of them when no
+/// names are given) on every dynamic benchmark changing every `period`
+/// evaluations, writing the results to
+/// `./results_copy/dynamic/{name}{SIZE}.csv`. The `reusable` strategy is a
+/// velocity rule evolved on every benchmark at once.
+fn run_dynamic(names: Option<&str>, canonical: Canonical, settings: Settings, period: usize) {
+    fs::create_dir_all("./results_copy/dynamic").unwrap();
+    let benchmarks = dynamic::benchmarks::<SIZE>(period);
+    let selected = |name: &str| names.is_none_or(|names| names.split(',').any(|n| n == name));
+    for strategy in baseline::baselines::<SIZE>(canonical) {
+        if selected(&strategy.name()) {
+            println!("Starting {} on dynamic", strategy.name());
+            run_dynamic_strategy(strategy.as_ref(), &strategy.name(), &benchmarks, settings);
+        }
+    }
+
+    if selected("reusable") {
+        println!("Evolving reusable velocity on dynamic");
+        let train = benchmarks
+            .iter()
+            .map(|benchmark| ((benchmark, training(settings)), 0.0))
+            .collect::<Vec<_>>();
+        let mut evolver =
+            Evolver::<Tracking<SIZE>>::new(100, (0.7, 0.3, 0.0), 3, 10, 100, 5, 30, &train);
+        let (chromosome, trace) = evolver.start();
+        write_trace(&format!("dynamic_reusable{SIZE}"), &trace);
+
+        let velocity = Velocity::<SIZE>::generate(&chromosome);
+        run_dynamic_strategy(&velocity, "reusable", &benchmarks, settings);
+    }
+}
+
+/// Runs a strategy 30 times on every benchmark, the `r`th run of every
+/// strategy seeing the same changes, writing the results to
+/// `./results_copy/dynamic/{name}{SIZE}.csv`.
+fn run_dynamic_strategy(
+    strategy: &(impl VelocityStrategy<SIZE> + ?Sized),
+    name: &str,
+    benchmarks: &[Dynamic<SIZE>],
+    settings: Settings,
+) {
+    let mut file = File::create(format!("./results_copy/dynamic/{name}{SIZE}.csv")).unwrap();
+    file.write_all(DynamicResult::HEADER).unwrap();
+    for benchmark in benchmarks {
+        let mut offline = Vec::with_capacity(30);
+        let mut before = Vec::with_capacity(30);
+        let start = Instant::now();
+        for r in 0..30 {
+            let environment = benchmark.environment(r);
+            pso(&settings, &benchmark.bounds, strategy, &|coords| {
+                environment.evaluate(coords)
+            });
+            offline.push(environment.offline_error());
+            before.push(environment.best_before_change());
+        }
+        let result = DynamicResult {
+            name: benchmark.name.clone(),
+            offline_error: mean_std(&offline),
+            best_before_change: mean_std(&before),
+            time: start.elapsed(),
+        };
+        file.write_all(result.to_csv().as_bytes()).unwrap();
+    }
+}
+
+struct DynamicResult {
+    name: String,
+    /// mean and standard deviation over the runs
+    offline_error: (f64, f64),
+    best_before_change: (f64, f64),
+    time: Duration,
+}
+
+impl DynamicResult {
+    const HEADER: &'static [u8] =
+        b"benchmark, offline_error, offline_error_std, best_before_change, best_before_change_std, time(s)\n";
+
+    fn to_csv(&self) -> String {
+        format!(
+            "{}, {}, {}, {}, {}, {:.4}\n",
+            self.name,
+            self.offline_error.0,
+            self.offline_error.1,
+            self.best_before_change.0,
+            self.best_before_change.1,
+            self.time.as_secs_f64()
+        )
+    }
+}
+
+/// Runs a strategy 30 times on every problem, writing the results to
+/// `./results_copy/pareto/{name}{SIZE}.csv`.
+fn run_pareto_strategy(
+    strategy: &(impl VelocityStrategy<SIZE> + ?Sized),
+    name: &str,
+    problems: &[Problem<SIZE>],
+    settings: Settings,
+) {
+    let mut file = File::create(format!("./results_copy/pareto/{name}{SIZE}.csv")).unwrap();
+    file.write_all(ParetoResult::HEADER).unwrap();
+    for problem in problems {
+        let mut hypervolumes = Vec::with_capacity(30);
+        let mut igds = Vec::with_capacity(30);
+        let start = Instant::now();
+        for _ in 0..30 {
+            let archive = pareto::mopso(&settings, &problem.bounds, strategy, &problem.func);
+            let front = archive.objectives();
+            hypervolumes.push(pareto::hypervolume(&front, &problem.reference));
+            igds.push(pareto::igd(&front, &problem.front));
+        }
+        let result = ParetoResult {
+            name: problem.name.clone(),
+            hypervolume: mean_std(&hypervolumes),
+            igd: mean_std(&igds),
+            time: start.elapsed(),
+        };
+        file.write_all(result.to_csv().as_bytes()).unwrap();
+    }
+}
+
+/// Mean and population standard deviation of `values`.
+fn mean_std(values: &[f64]) -> (f64, f64) {
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let std =
+        (values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / values.len() as f64).sqrt();
+
+    (mean, std)
+}
+
+struct ParetoResult {
+    name: String,
+    /// mean and standard deviation over the runs
+    hypervolume: (f64, f64),
+    igd: (f64, f64),
+    time: Duration,
+}
+
+impl ParetoResult {
+    const HEADER: &'static [u8] = b"problem, hypervolume, hypervolume_std, igd, igd_std, time(s)\n";
+
+    fn to_csv(&self) -> String {
+        format!(
+            "{}, {}, {}, {}, {}, {:.4}\n",
+            self.name,
+            self.hypervolume.0,
+            self.hypervolume.1,
+            self.igd.0,
+            self.igd.1,
+            self.time.as_secs_f64()
+        )
+    }
+}
+
 struct FunctionResult {
+    name: String,
     min: f64,
     mean: f64,
     std: f64,
     time: Duration,
+    /// mean number of function evaluations per run
+    evaluations: f64,
+    /// where the runs ended relative to the known optima
+    placement: Option<Placement>,
+    /// best and mean noiseless fitness of the final solutions, which differ
+    /// from `min` and `mean` for noisy functions
+    true_min: f64,
+    true_mean: f64,
+    /// fraction of runs whose final solution satisfies every constraint
+    feasible: f64,
 }
 
 impl FunctionResult {
+    const HEADER: &'static [u8] =
+        b"function, min, mean, std, time(s), evaluations, distance, far, true_min, true_mean, feasible\n";
+
     fn to_csv(&self) -> String {
         format!(
-            "{}, {}, {}, {:.4}\n",
+            "{}, {}, {}, {}, {:.4}, {}, {}, {}, {}, {}\n",
+            self.name,
             self.min,
             self.mean,
             self.std,
-            self.time.as_secs_f64()
+            self.time.as_secs_f64(),
+            self.evaluations,
+            Placement::to_csv(self.placement.as_ref()),
+            self.true_min,
+            self.true_mean,
+            self.feasible
         )
     }
 }
 
-pub fn canonical_velocity<const DIMS: usize>(
-    current: &Particle<DIMS>,
-    best: &Particle<DIMS>,
-) -> Vector<DIMS> {
-    let c1 = 2.0;
-    let c2 = 2.0;
-    let w = 0.4;
-    let (r1, r2): (f64, f64) = rand::random();
+/// Writes the convergence history of every run to
+/// `./results_copy/convergence/{label}.csv`.
+fn write_histories(label: &str, histories: &[History]) {
+    fs::create_dir_all("./results_copy/convergence").unwrap();
+    let mut file = File::create(format!("./results_copy/convergence/{label}.csv")).unwrap();
+    file.write_all(b"run, iteration, best, diversity\n")
+        .unwrap();
+    for (run, history) in histories.iter().enumerate() {
+        file.write_all(history.to_csv(run).as_bytes()).unwrap();
+    }
+}
 
-    let vel = w * current.velocity()
-        + c1 * r1 * (best.coordinates() - current.coordinates())
-        + c2 * r2 * (current.best() - current.coordinates());
+/// Writes the success rate, mean evaluations and expected running time to
+/// reach every fixed target to `./results_copy/targets/{label}.csv`.
+fn write_targets(label: &str, attempts: &[Attempt]) {
+    fs::create_dir_all("./results_copy/targets").unwrap();
+    let mut file = File::create(format!("./results_copy/targets/{label}.csv")).unwrap();
+    file.write_all(b"epsilon, success_rate, mean_evaluations, ert\n")
+        .unwrap();
+    for epsilon in EPSILONS {
+        file.write_all(FixedTarget::new(attempts, epsilon).to_csv().as_bytes())
+            .unwrap();
+    }
+}
 
-    vel
+/// Writes the generation trace of an evolution to
+/// `./results_copy/trace/{label}.csv`.
+fn write_trace(label: &str, trace: &Trace) {
+    fs::create_dir_all("./results_copy/trace").unwrap();
+    let mut file = File::create(format!("./results_copy/trace/{label}.csv")).unwrap();
+    file.write_all(
+        b"generation, best, mean, worst, genotype_diversity, phenotype_diversity, size, expression\n",
+    )
+    .unwrap();
+    file.write_all(trace.to_csv().as_bytes()).unwrap();
 }
 
-fn run_canonical_pso<const SIZE: usize>(
+/// Runs a fixed velocity strategy 30 times on every function.
+fn run_strategy<const SIZE: usize>(
+    strategy: &(impl VelocityStrategy<SIZE> + ?Sized),
     functions: &[impl Borrow<function::Function<SIZE>>],
+    label: &str,
+    settings: Settings,
 ) -> FunctionResult {
     let mut results = Vec::with_capacity(30);
+    let mut histories = Vec::with_capacity(30);
+    let mut attempts = Vec::with_capacity(30);
+    let mut placements = Vec::with_capacity(30);
+    let mut truths = Vec::with_capacity(30);
+    let mut feasible = 0;
+    let mut evaluations = 0;
     let start = Instant::now();
     for r in 0..30 {
         // println!("Run: {r}");
@@ -173,18 +818,26 @@ fn run_canonical_pso<const SIZE: usize>(
         // running the pso
         for function in functions {
             let function = function.borrow();
-            let particle = pso(
-                40,
-                2500,
-                &function.bounds,
-                canonical_velocity,
-                &function.func,
-            );
-            let minima = (function.func)(&particle.coordinates());
-            results.push(minima)
+            let run = optimise(&settings, &function.bounds, strategy, function);
+            let minima = (function.func)(&run.best);
+            results.push(minima);
+            placements.push((function.distance(&run.best), function.diagonal()));
+            truths.push(function.true_fitness(&run.best));
+            if function.violation(&run.best) == 0.0 {
+                feasible += 1;
+            }
+            evaluations += run.evaluations;
+            attempts.push(Attempt {
+                minima: function.minima,
+                improvements: run.history.improvements.clone(),
+                evaluations: run.evaluations,
+            });
+            histories.push(run.history);
         }
     }
     let end = start.elapsed();
+    write_histories(label, &histories);
+    write_targets(label, &attempts);
 
     let mut min = f64::MAX;
     for result in &results {
@@ -198,50 +851,72 @@ fn run_canonical_pso<const SIZE: usize>(
         .sqrt();
 
     FunctionResult {
+        name: functions
+            .iter()
+            .map(|function| function.borrow().name.as_str())
+            .collect::<Vec<_>>()
+            .join(" + "),
         min,
         mean,
         std,
         time: end,
+        evaluations: evaluations as f64 / results.len() as f64,
+        placement: Placement::new(&placements),
+        true_min: truths.iter().copied().fold(f64::MAX, f64::min),
+        true_mean: truths.iter().sum::<f64>() / truths.len() as f64,
+        feasible: feasible as f64 / truths.len() as f64,
     }
 }
 
-fn run_functions<const SIZE: usize>(function: function::Function<SIZE>) -> FunctionResult {
+fn run_functions<const SIZE: usize>(
+    function: function::Function<SIZE>,
+    label: &str,
+    settings: Settings,
+) -> FunctionResult {
     let mut results = Vec::with_capacity(30);
+    let mut histories = Vec::with_capacity(30);
+    let mut attempts = Vec::with_capacity(30);
+    let mut placements = Vec::with_capacity(30);
+    let mut truths = Vec::with_capacity(30);
+    let mut feasible = 0;
+    let mut evaluations = 0;
     let start = Instant::now();
     for r in 0..30 {
         // println!("Run: {r}");
         let train = [(
-            (
-                &function.func as &Box<dyn for<'a> Fn(&'a Vector<SIZE>) -> f64 + Send>,
-                function.bounds.as_slice(),
-            ),
+            (&function, function.bounds.as_slice(), training(settings)),
             function.minima,
         )];
-        let mut ge =
-            GE::<(&Box<dyn Fn(&Vector<SIZE>) -> f64 + Send>, &[Bound]), f64, Velocity<SIZE>>::new(
-                100,
-                (0.7, 0.3, 0.0),
-                3,
-                10,
-                100,
-                5,
-                1,
-                &train,
-            );
-        let chromosome = ge.start();
+        let mut evolver =
+            Evolver::<Velocity<SIZE>>::new(100, (0.7, 0.3, 0.0), 3, 10, 100, 5, 1, &train);
+        let (chromosome, trace) = evolver.start();
+        write_trace(&format!("{label}_r{r}"), &trace);
 
         // creating the velocity equation
         let velocity = Velocity::generate(&chromosome);
         // println!("{:?}", velocity);
         // dbg!(&velocity);
-        let func = |current: &_, best: &_| velocity.runner(current, best);
 
         // running the pso
-        let particle = pso(100, 100, &function.bounds, func, &function.func);
-        let minima = (function.func)(&particle.coordinates());
-        results.push(minima)
+        let run = optimise(&settings, &function.bounds, &velocity, &function);
+        let minima = (function.func)(&run.best);
+        results.push(minima);
+        placements.push((function.distance(&run.best), function.diagonal()));
+        truths.push(function.true_fitness(&run.best));
+        if function.violation(&run.best) == 0.0 {
+            feasible += 1;
+        }
+        evaluations += run.evaluations;
+        attempts.push(Attempt {
+            minima: function.minima,
+            improvements: run.history.improvements.clone(),
+            evaluations: run.evaluations,
+        });
+        histories.push(run.history);
     }
     let end = start.elapsed();
+    write_histories(label, &histories);
+    write_targets(label, &attempts);
 
     let mut min = f64::MAX;
     for result in &results {
@@ -255,15 +930,236 @@ fn run_functions<const SIZE: usize>(function: function::Function<SIZE>) -> Funct
         .sqrt();
 
     FunctionResult {
+        name: function.name.clone(),
         min,
         mean,
         std,
         time: end,
+        evaluations: evaluations as f64 / results.len() as f64,
+        placement: Placement::new(&placements),
+        true_min: truths.iter().copied().fold(f64::MAX, f64::min),
+        true_mean: truths.iter().sum::<f64>() / truths.len() as f64,
+        feasible: feasible as f64 / truths.len() as f64,
+    }
+}
+
+/// Value following `--{name}` in the command line arguments.
+fn option<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg.strip_prefix("--") == Some(name))
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+/// Pso settings with the evaluation budget configured by `--evaluations <n>` or
+/// `--evaluations-per-dimension <n>` (`BUDGET` by default), velocity clamping
+/// by `--clamp <fraction>`, the boundary policy by
+/// `--boundary (none|clamp|reflect|reinitialise|penalty)`, the topology by
+/// `--topology (star|ring|vonneumann|random)` and the constraint handling by
+/// `--handling (feasibility|penalty|epsilon)` with the positive penalty
+/// coefficient of `--penalty <coefficient>` and the initial level of
+/// `--epsilon <level>`.
+fn settings(args: &[String]) -> Settings {
+    Settings {
+        evaluations: option(args, "evaluations").map(|evaluations| evaluations.parse().unwrap()),
+        evaluations_per_dimension: Some(
+            option(args, "evaluations-per-dimension")
+                .map_or(BUDGET, |evaluations| evaluations.parse().unwrap()),
+        ),
+        vmax: option(args, "clamp").map(|clamp| clamp.parse().unwrap()),
+        boundary: match option(args, "boundary") {
+            None | Some("none") => Boundary::None,
+            Some("clamp") => Boundary::Clamp,
+            Some("reflect") => Boundary::Reflect,
+            Some("reinitialise") => Boundary::Reinitialise,
+            Some("penalty") => Boundary::Penalty,
+            Some(boundary) => panic!("Unknown boundary policy: {boundary}"),
+        },
+        topology: match option(args, "topology") {
+            None | Some("star") => Topology::Star,
+            Some("ring") => Topology::Ring,
+            Some("vonneumann") => Topology::VonNeumann,
+            Some("random") => Topology::Random(3),
+            Some(topology) => panic!("Unknown topology: {topology}"),
+        },
+        handling: match option(args, "handling") {
+            None | Some("feasibility") => Handling::Feasibility,
+            Some("penalty") => Handling::Penalty(
+                match option(args, "penalty").map_or(1e6, |penalty| penalty.parse().unwrap()) {
+                    penalty if penalty > 0.0 => penalty,
+                    penalty => panic!("The penalty coefficient must be positive, not {penalty}"),
+                },
+            ),
+            Some("epsilon") => Handling::Epsilon(
+                option(args, "epsilon").map_or(1.0, |epsilon| epsilon.parse().unwrap()),
+            ),
+            Some(handling) => panic!("Unknown constraint handling: {handling}"),
+        },
+        diversity: true,
+        ..Settings::default()
+    }
+}
+
+/// Benchmark suite selected by `--suite (classic|cec|bbob|custom|plugin)`,
+/// with the cec suite generated from `--seed <n>`, the bbob suite of
+/// `--instance <n>`, the custom suite declared in the file of `--functions
+/// <path>` and the plugin suite served by the program run by `--plugin
+/// <command>`.
+fn suite(args: &[String]) -> Suite {
+    match option(args, "suite") {
+        None | Some("classic") => Suite::Classic,
+        Some("cec") => Suite::Cec(option(args, "seed").map_or(0, |seed| seed.parse().unwrap())),
+        Some("bbob") => {
+            Suite::Bbob(option(args, "instance").map_or(1, |instance| instance.parse().unwrap()))
+        }
+        Some("custom") => Suite::Custom(
+            option(args, "functions")
+                .expect("The custom suite needs --functions <path>")
+                .to_string(),
+        ),
+        Some("plugin") => Suite::Plugin(
+            option(args, "plugin")
+                .expect("The plugin suite needs --plugin <command>")
+                .to_string(),
+        ),
+        Some(suite) => panic!("Unknown suite: {suite}"),
+    }
+}
+
+/// Bbob instances selected by `--instances <first>-<last>` or `--instances
+/// <i>[,...]`, every instance of the current suite by default.
+fn instances(args: &[String]) -> Vec<usize> {
+    match option(args, "instances") {
+        None => bbob::INSTANCES.collect(),
+        Some(instances) => match instances.split_once('-') {
+            Some((first, last)) => (first.parse().unwrap()..=last.parse().unwrap()).collect(),
+            None => instances.split(',').map(|i| i.parse().unwrap()).collect(),
+        },
+    }
+}
+
+/// Transformations of every function selected by `--transform
+/// (shift|rotate|scale)[,...]` and the noise selected by `--noise
+/// (additive|multiplicative)-(gaussian|uniform)` of `--noise-level <level>`,
+/// drawn from `--seed <n>`.
+fn transform(args: &[String]) -> Transform {
+    let transforms = option(args, "transform")
+        .map(|transforms| transforms.split(',').collect::<Vec<_>>())
+        .unwrap_or_default();
+    if let Some(transform) = transforms
+        .iter()
+        .find(|transform| !["shift", "rotate", "scale"].contains(transform))
+    {
+        panic!("Unknown transform: {transform}");
+    }
+
+    Transform {
+        shift: transforms.contains(&"shift"),
+        rotate: transforms.contains(&"rotate"),
+        scale: transforms.contains(&"scale"),
+        noise: option(args, "noise").map(|noise| Noise {
+            combination: match noise.split_once('-').map(|(combination, _)| combination) {
+                Some("additive") => Combination::Additive,
+                Some("multiplicative") => Combination::Multiplicative,
+                _ => panic!("Unknown noise: {noise}"),
+            },
+            distribution: match noise.split_once('-').map(|(_, distribution)| distribution) {
+                Some("gaussian") => Distribution::Gaussian,
+                Some("uniform") => Distribution::Uniform,
+                _ => panic!("Unknown noise: {noise}"),
+            },
+            level: option(args, "noise-level").map_or(1.0, |level| level.parse().unwrap()),
+        }),
+        seed: option(args, "seed").map_or(0, |seed| seed.parse().unwrap()),
+    }
+}
+
+/// Canonical pso configured by `--w`, `--c1`, `--c2` and `--random
+/// (dimension|particle)`, whose velocity is clamped by `--clamp` like that of
+/// every other strategy.
+fn canonical(args: &[String]) -> Canonical {
+    let default = Canonical::default();
+    let value = |name, default| option(args, name).map_or(default, |value| value.parse().unwrap());
+
+    Canonical {
+        w: value("w", default.w),
+        c1: value("c1", default.c1),
+        c2: value("c2", default.c2),
+        random: match option(args, "random") {
+            None | Some("dimension") => Random::PerDimension,
+            Some("particle") => Random::PerParticle,
+            Some(random) => panic!("Unknown random mode: {random}"),
+        },
     }
 }
 
 fn main() {
-    run_all_functions()
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match args.first().map(String::as_str) {
+        None | Some("run") => run_all_functions(suite(&args), transform(&args), settings(&args)),
+        Some("baseline") => run_baselines(
+            option(&args, "strategies"),
+            canonical(&args),
+            suite(&args),
+            transform(&args),
+            settings(&args),
+        ),
+        Some("coco") => run_coco(
+            option(&args, "strategies"),
+            &instances(&args),
+            canonical(&args),
+            settings(&args),
+            option(&args, "output").unwrap_or("./results_copy/coco"),
+        ),
+        Some("binary") => run_binary(
+            option(&args, "strategies"),
+            canonical(&args),
+            settings(&args),
+        ),
+        Some("constrained") => run_constrained(
+            option(&args, "strategies"),
+            canonical(&args),
+            settings(&args),
+        ),
+        Some("dynamic") => run_dynamic(
+            option(&args, "strategies"),
+            canonical(&args),
+            Settings {
+                detection: option(&args, "detection") != Some("off"),
+                ..settings(&args)
+            },
+            option(&args, "period").map_or(dynamic::PERIOD, |period| period.parse().unwrap()),
+        ),
+        Some("realworld") => run_realworld(
+            option(&args, "strategies"),
+            canonical(&args),
+            settings(&args),
+        ),
+        Some("pareto") => run_pareto(
+            option(&args, "strategies"),
+            canonical(&args),
+            settings(&args),
+        ),
+        Some("plot") => plot::plot(
+            option(&args, "input").unwrap_or("./results_copy"),
+            option(&args, "output").unwrap_or("./results_copy/plots"),
+            option(&args, "size").map_or(100, |size| size.parse().unwrap()),
+        ),
+        Some("report") => report::report(
+            option(&args, "input").unwrap_or("./results_copy"),
+            option(&args, "output").unwrap_or("./results_copy/report"),
+            &option(&args, "sizes")
+                .unwrap_or("30,100")
+                .split(',')
+                .map(|size| size.parse().unwrap())
+                .collect::<Vec<_>>(),
+            &option(&args, "strategies")
+                .unwrap_or("canonical,disposable,reusable")
+                .split(',')
+                .collect::<Vec<_>>(),
+        ),
+        Some(command) => eprintln!("Unknown command: {command}"),
+    }
     // let func = Function {
     //     func: Box::new(|coords: &Vector<30>| {
     //         0.26 * (coords[0] * coords[0] + coords[1] * coords[1]) - 0.48 * coords[0] * coords[1]
@@ -0,0 +1,158 @@
+/// Precisions above the known minimum used as fixed targets.
+pub const EPSILONS: [f64; 5] = [1e-1, 1e-2, 1e-4, 1e-6, 1e-8];
+
+/// What a single run needs to be judged against fixed targets.
+pub struct Attempt {
+    pub minima: f64,
+    /// `(evaluations, fitness)` for every improvement of the best fitness
+    pub improvements: Vec<(usize, f64)>,
+    /// total number of evaluations used by the run
+    pub evaluations: usize,
+}
+
+impl Attempt {
+    /// Number of evaluations needed to reach `minima + epsilon`.
+    pub fn hit(&self, epsilon: f64) -> Option<usize> {
+        self.improvements
+            .iter()
+            .find(|(_, fitness)| *fitness <= self.minima + epsilon)
+            .map(|(evaluations, _)| *evaluations)
+    }
+}
+
+pub struct FixedTarget {
+    pub epsilon: f64,
+    /// fraction of runs which reached the target
+    pub success_rate: f64,
+    /// mean evaluations to reach the target over the successful runs
+    pub evaluations: Option<f64>,
+    /// expected running time, the evaluations of every run (up to the hit
+    /// for successful runs) divided by the number of successful runs
+    pub ert: f64,
+}
+
+impl FixedTarget {
+    pub fn new(attempts: &[Attempt], epsilon: f64) -> Self {
+        let hits = attempts
+            .iter()
+            .filter_map(|attempt| attempt.hit(epsilon))
+            .collect::<Vec<_>>();
+        let spent = attempts
+            .iter()
+            .map(|attempt| attempt.hit(epsilon).unwrap_or(attempt.evaluations))
+            .sum::<usize>();
+
+        Self {
+            epsilon,
+            success_rate: hits.len() as f64 / attempts.len() as f64,
+            evaluations: (!hits.is_empty())
+                .then(|| hits.iter().sum::<usize>() as f64 / hits.len() as f64),
+            ert: if hits.is_empty() {
+                f64::INFINITY
+            } else {
+                spent as f64 / hits.len() as f64
+            },
+        }
+    }
+
+    pub fn to_csv(&self) -> String {
+        format!(
+            "{}, {:.4}, {}, {}\n",
+            self.epsilon,
+            self.success_rate,
+            self.evaluations
+                .map_or(String::new(), |e| format!("{e:.1}")),
+            self.ert
+        )
+    }
+}
+
+/// Fraction of the diagonal of the bounds beyond which a run is judged to
+/// have ended away from every known optimum.
+pub const FAR: f64 = 0.01;
+
+/// Where a set of runs ended in decision space relative to the known optima.
+pub struct Placement {
+    /// mean distance to the nearest known optimum
+    pub distance: f64,
+    /// fraction of runs which ended further than `FAR` of the diagonal of the
+    /// bounds from every known optimum, which flags runs converging to the
+    /// wrong basin however good their fitness
+    pub far: f64,
+}
+
+impl Placement {
+    /// Placement of runs given as `(distance, diagonal)`, where the distance
+    /// to the nearest optimum is `None` for functions without known optima.
+    pub fn new(runs: &[(Option<f64>, f64)]) -> Option<Self> {
+        let runs = runs
+            .iter()
+            .map(|(distance, diagonal)| distance.map(|distance| (distance, *diagonal)))
+            .collect::<Option<Vec<_>>>()?;
+        if runs.is_empty() {
+            return None;
+        }
+        let n = runs.len() as f64;
+
+        Some(Self {
+            distance: runs.iter().map(|(distance, _)| distance).sum::<f64>() / n,
+            far: runs
+                .iter()
+                .filter(|(distance, diagonal)| *distance > FAR * diagonal)
+                .count() as f64
+                / n,
+        })
+    }
+
+    pub fn to_csv(placement: Option<&Self>) -> String {
+        placement.map_or(", ".to_string(), |placement| {
+            format!("{}, {:.4}", placement.distance, placement.far)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(improvements: &[(usize, f64)], evaluations: usize) -> Attempt {
+        Attempt {
+            minima: 1.0,
+            improvements: improvements.to_vec(),
+            evaluations,
+        }
+    }
+
+    #[test]
+    fn hit() {
+        let attempt = attempt(&[(1, 5.0), (40, 1.5), (90, 1.05), (300, 1.0)], 1000);
+        assert_eq!(attempt.hit(1.0), Some(40));
+        assert_eq!(attempt.hit(0.1), Some(90));
+        assert_eq!(attempt.hit(0.0), Some(300));
+        assert_eq!(attempt.hit(-1.0), None);
+    }
+
+    #[test]
+    fn ert() {
+        // two runs reach the target after 100 and 300 evaluations and one
+        // fails after spending its 1000, so ert = (100 + 300 + 1000) / 2
+        let attempts = [
+            attempt(&[(1, 9.0), (100, 1.05)], 500),
+            attempt(&[(1, 9.0), (300, 1.01)], 400),
+            attempt(&[(1, 9.0), (800, 1.5)], 1000),
+        ];
+        let target = FixedTarget::new(&attempts, 0.1);
+        assert_eq!(target.success_rate, 2.0 / 3.0);
+        assert_eq!(target.evaluations, Some(200.0));
+        assert_eq!(target.ert, 700.0);
+    }
+
+    #[test]
+    fn unreached() {
+        let attempts = [attempt(&[(1, 9.0)], 500), attempt(&[], 500)];
+        let target = FixedTarget::new(&attempts, 0.1);
+        assert_eq!(target.success_rate, 0.0);
+        assert_eq!(target.evaluations, None);
+        assert_eq!(target.ert, f64::INFINITY);
+    }
+}
@@ -0,0 +1,616 @@
+use crate::evolver::Expression;
+use crate::strategy::{Context, VelocityStrategy};
+use crate::swarm::{Boundary, Particle, Settings, IDLE};
+use crate::velocity::Velocity;
+use mikes_ge::grammar::Grammar;
+use mikes_pso::{bounds::Bound, vector::Vector};
+use rand::Rng;
+use std::f64::consts::PI;
+use std::fmt::{self, Display};
+
+/// Number of non-dominated solutions kept by the multi-objective pso.
+pub const ARCHIVE: usize = 100;
+
+/// Number of objectives of the dtlz problems.
+const DTLZ: usize = 3;
+
+/// Objectives of a position.
+type Objectives<const SIZE: usize> = Box<dyn Fn(&Vector<SIZE>) -> Vec<f64> + Send>;
+
+/// Objectives of a position given as a slice.
+type Sliced = Box<dyn Fn(&[f64]) -> Vec<f64> + Send>;
+
+/// A benchmark with several objectives which are all minimised.
+pub struct Problem<const SIZE: usize> {
+    pub name: String,
+    pub func: Objectives<SIZE>,
+    pub bounds: Vec<Bound>,
+    /// points spread along the true Pareto front, used by the igd
+    pub front: Vec<Vec<f64>>,
+    /// reference point of the hypervolume, 10% beyond the worst point of the
+    /// front in every objective
+    pub reference: Vec<f64>,
+}
+
+fn problem<const SIZE: usize>(
+    name: &str,
+    func: Sliced,
+    bounds: Vec<Bound>,
+    front: Vec<Vec<f64>>,
+) -> Problem<SIZE> {
+    let reference = (0..front[0].len())
+        .map(|i| 1.1 * front.iter().map(|point| point[i]).fold(f64::MIN, f64::max))
+        .collect();
+
+    Problem {
+        name: name.to_string(),
+        func: Box::new(move |coords: &Vector<SIZE>| {
+            func(&coords.iter().copied().collect::<Vec<_>>())
+        }),
+        bounds,
+        front,
+        reference,
+    }
+}
+
+/// The zdt problems with two objectives and the dtlz problems with three
+/// objectives, all at `SIZE` variables.
+pub fn problems<const SIZE: usize>() -> Vec<Problem<SIZE>> {
+    let unit = vec![Bound::from((0.0, 1.0)); SIZE];
+    // distance function of zdt1 to zdt3
+    let g = |x: &[f64]| 1.0 + 9.0 * x.iter().skip(1).sum::<f64>() / (SIZE - 1) as f64;
+
+    vec![
+        problem(
+            "zdt1",
+            Box::new(move |x: &[f64]| {
+                let g = g(x);
+                vec![x[0], g * (1.0 - (x[0] / g).sqrt())]
+            }),
+            unit.clone(),
+            curve(0.0, 1.0, |f1| 1.0 - f1.sqrt()),
+        ),
+        problem(
+            "zdt2",
+            Box::new(move |x: &[f64]| {
+                let g = g(x);
+                vec![x[0], g * (1.0 - (x[0] / g).powi(2))]
+            }),
+            unit.clone(),
+            curve(0.0, 1.0, |f1| 1.0 - f1 * f1),
+        ),
+        problem(
+            "zdt3",
+            Box::new(move |x: &[f64]| {
+                let g = g(x);
+                let r = x[0] / g;
+                vec![x[0], g * (1.0 - r.sqrt() - r * (10.0 * PI * x[0]).sin())]
+            }),
+            unit.clone(),
+            curve(0.0, 1.0, |f1| 1.0 - f1.sqrt() - f1 * (10.0 * PI * f1).sin()),
+        ),
+        problem(
+            "zdt4",
+            Box::new(|x: &[f64]| {
+                let g = 1.0
+                    + 10.0 * (SIZE - 1) as f64
+                    + x.iter()
+                        .skip(1)
+                        .map(|x| x * x - 10.0 * (4.0 * PI * x).cos())
+                        .sum::<f64>();
+                vec![x[0], g * (1.0 - (x[0] / g).sqrt())]
+            }),
+            std::iter::once(Bound::from((0.0, 1.0)))
+                .chain(std::iter::repeat(Bound::from((-5.0, 5.0))))
+                .take(SIZE)
+                .collect(),
+            curve(0.0, 1.0, |f1| 1.0 - f1.sqrt()),
+        ),
+        problem(
+            "zdt6",
+            Box::new(|x: &[f64]| {
+                let f1 = 1.0 - (-4.0 * x[0]).exp() * (6.0 * PI * x[0]).sin().powi(6);
+                let g = 1.0 + 9.0 * (x.iter().skip(1).sum::<f64>() / (SIZE - 1) as f64).powf(0.25);
+                vec![f1, g * (1.0 - (f1 / g).powi(2))]
+            }),
+            unit.clone(),
+            // f1 cannot be any smaller
+            curve(0.2807753191, 1.0, |f1| 1.0 - f1 * f1),
+        ),
+        problem(
+            "dtlz1",
+            Box::new(|x: &[f64]| {
+                let g = 100.0 * multimodal(&x[DTLZ - 1..]);
+                linear(&x[..DTLZ - 1], g)
+            }),
+            unit.clone(),
+            simplex(0.5),
+        ),
+        problem(
+            "dtlz2",
+            Box::new(|x: &[f64]| spherical(&x[..DTLZ - 1], unimodal(&x[DTLZ - 1..]))),
+            unit.clone(),
+            sphere(),
+        ),
+        problem(
+            "dtlz3",
+            Box::new(|x: &[f64]| {
+                let g = 100.0 * multimodal(&x[DTLZ - 1..]);
+                spherical(&x[..DTLZ - 1], g)
+            }),
+            unit.clone(),
+            sphere(),
+        ),
+        problem(
+            "dtlz4",
+            Box::new(|x: &[f64]| {
+                let angles = x[..DTLZ - 1]
+                    .iter()
+                    .map(|x| x.powi(100))
+                    .collect::<Vec<_>>();
+                spherical(&angles, unimodal(&x[DTLZ - 1..]))
+            }),
+            unit,
+            sphere(),
+        ),
+    ]
+}
+
+/// Distance function of dtlz2 and dtlz4.
+fn unimodal(x: &[f64]) -> f64 {
+    x.iter().map(|x| (x - 0.5).powi(2)).sum()
+}
+
+/// Distance function of dtlz1 and dtlz3, without its factor of 100.
+fn multimodal(x: &[f64]) -> f64 {
+    x.len() as f64
+        + x.iter()
+            .map(|x| (x - 0.5).powi(2) - (20.0 * PI * (x - 0.5)).cos())
+            .sum::<f64>()
+}
+
+/// Objectives on the hyperplane `sum f = 0.5` scaled by `1 + g`.
+fn linear(position: &[f64], g: f64) -> Vec<f64> {
+    (0..DTLZ)
+        .map(|i| {
+            let m = DTLZ - 1 - i;
+            let f = 0.5 * (1.0 + g) * position[..m].iter().product::<f64>();
+            if i == 0 {
+                f
+            } else {
+                f * (1.0 - position[m])
+            }
+        })
+        .collect()
+}
+
+/// Objectives on the unit sphere scaled by `1 + g`.
+fn spherical(position: &[f64], g: f64) -> Vec<f64> {
+    (0..DTLZ)
+        .map(|i| {
+            let m = DTLZ - 1 - i;
+            let f = (1.0 + g)
+                * position[..m]
+                    .iter()
+                    .map(|x| (x * PI / 2.0).cos())
+                    .product::<f64>();
+            if i == 0 {
+                f
+            } else {
+                f * (position[m] * PI / 2.0).sin()
+            }
+        })
+        .collect()
+}
+
+/// Non-dominated points of `(f1, f2(f1))` for `f1` evenly spread over
+/// `[first, last]`.
+fn curve(first: f64, last: f64, f2: impl Fn(f64) -> f64) -> Vec<Vec<f64>> {
+    const POINTS: usize = 1000;
+    let mut front = Vec::new();
+    let mut lowest = f64::INFINITY;
+    // f1 only grows so a point is dominated exactly when an earlier f2 is lower
+    for i in 0..POINTS {
+        let f1 = first + (last - first) * i as f64 / (POINTS - 1) as f64;
+        let f2 = f2(f1);
+        if f2 < lowest {
+            lowest = f2;
+            front.push(vec![f1, f2]);
+        }
+    }
+
+    front
+}
+
+/// Evenly spread points of the simplex `sum f = total` in three objectives.
+fn simplex(total: f64) -> Vec<Vec<f64>> {
+    const DIVISIONS: usize = 30;
+    let mut front = Vec::new();
+    for i in 0..=DIVISIONS {
+        for j in 0..=DIVISIONS - i {
+            let k = DIVISIONS - i - j;
+            front.push(
+                [i, j, k]
+                    .iter()
+                    .map(|&n| total * n as f64 / DIVISIONS as f64)
+                    .collect(),
+            );
+        }
+    }
+
+    front
+}
+
+/// Points of the simplex projected onto the unit sphere.
+fn sphere() -> Vec<Vec<f64>> {
+    simplex(1.0)
+        .into_iter()
+        .map(|point| {
+            let norm = point.iter().map(|f| f * f).sum::<f64>().sqrt();
+            point.iter().map(|f| f / norm).collect()
+        })
+        .collect()
+}
+
+/// Whether `x` is no worse than `y` in every objective and better in one.
+pub fn dominates(x: &[f64], y: &[f64]) -> bool {
+    x.iter().zip(y).all(|(x, y)| x <= y) && x.iter().zip(y).any(|(x, y)| x < y)
+}
+
+/// Crowding distance of every point, the size of the cuboid between its
+/// neighbours along every objective normalised by the range of the
+/// objective. The extreme points are never crowded.
+fn crowding(points: &[&[f64]]) -> Vec<f64> {
+    let mut distances = vec![0.0; points.len()];
+    if points.len() <= 2 {
+        return vec![f64::INFINITY; points.len()];
+    }
+    for objective in 0..points[0].len() {
+        let mut order = (0..points.len()).collect::<Vec<_>>();
+        order.sort_by(|&i, &j| points[i][objective].total_cmp(&points[j][objective]));
+        let (first, last) = (order[0], order[order.len() - 1]);
+        let range = points[last][objective] - points[first][objective];
+        distances[first] = f64::INFINITY;
+        distances[last] = f64::INFINITY;
+        if range > 0.0 {
+            for w in order.windows(3) {
+                distances[w[1]] += (points[w[2]][objective] - points[w[0]][objective]) / range;
+            }
+        }
+    }
+
+    distances
+}
+
+/// Bounded archive of the non-dominated solutions found so far, which drops
+/// its most crowded member when full.
+pub struct Archive<const SIZE: usize> {
+    capacity: usize,
+    members: Vec<(Vector<SIZE>, Vec<f64>)>,
+}
+
+impl<const SIZE: usize> Archive<SIZE> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            members: Vec::with_capacity(capacity + 1),
+        }
+    }
+
+    /// Adds a solution unless a member dominates or equals it, removing every
+    /// member it dominates.
+    pub fn insert(&mut self, coordinates: Vector<SIZE>, objectives: Vec<f64>) {
+        if self
+            .members
+            .iter()
+            .any(|(_, member)| *member == objectives || dominates(member, &objectives))
+        {
+            return;
+        }
+        self.members
+            .retain(|(_, member)| !dominates(&objectives, member));
+        self.members.push((coordinates, objectives));
+
+        if self.members.len() > self.capacity {
+            let crowding = crowding(&self.objectives());
+            let crowded = (0..crowding.len())
+                .min_by(|&i, &j| crowding[i].total_cmp(&crowding[j]))
+                .unwrap();
+            self.members.swap_remove(crowded);
+        }
+    }
+
+    /// `n` leaders drawn by binary tournaments which favour the members in
+    /// the least crowded regions of the front.
+    pub fn leaders(&self, n: usize) -> Vec<Vector<SIZE>> {
+        let mut rng = rand::thread_rng();
+        let crowding = crowding(&self.objectives());
+        (0..n)
+            .map(|_| {
+                let i = rng.gen_range(0..self.members.len());
+                let j = rng.gen_range(0..self.members.len());
+                if crowding[i] >= crowding[j] {
+                    self.members[i].0
+                } else {
+                    self.members[j].0
+                }
+            })
+            .collect()
+    }
+
+    pub fn objectives(&self) -> Vec<&[f64]> {
+        self.members
+            .iter()
+            .map(|(_, objectives)| objectives.as_slice())
+            .collect()
+    }
+}
+
+/// Volume dominated by `front` and bounded by `reference`, computed exactly
+/// by slicing along the last objective. Points which do not dominate the
+/// reference add nothing.
+pub fn hypervolume(front: &[&[f64]], reference: &[f64]) -> f64 {
+    let points = front
+        .iter()
+        .copied()
+        .filter(|point| point.iter().zip(reference).all(|(f, r)| f < r))
+        .collect::<Vec<_>>();
+
+    slices(&points, reference)
+}
+
+fn slices(points: &[&[f64]], reference: &[f64]) -> f64 {
+    let m = reference.len();
+    if points.is_empty() {
+        return 0.0;
+    }
+    if m == 1 {
+        return reference[0] - points.iter().map(|point| point[0]).fold(f64::MAX, f64::min);
+    }
+
+    let mut points = points.to_vec();
+    points.sort_by(|x, y| x[m - 1].total_cmp(&y[m - 1]));
+    let mut volume = 0.0;
+    for i in 0..points.len() {
+        let depth = points
+            .get(i + 1)
+            .map_or(reference[m - 1], |next| next[m - 1])
+            - points[i][m - 1];
+        if depth > 0.0 {
+            let slice = points[..=i]
+                .iter()
+                .map(|point| &point[..m - 1])
+                .collect::<Vec<_>>();
+            volume += depth * slices(&slice, &reference[..m - 1]);
+        }
+    }
+
+    volume
+}
+
+/// Inverted generational distance, the mean distance from every point of the
+/// true front to the closest point of `front`.
+pub fn igd(front: &[&[f64]], reference: &[Vec<f64>]) -> f64 {
+    reference
+        .iter()
+        .map(|r| {
+            front
+                .iter()
+                .map(|point| {
+                    point
+                        .iter()
+                        .zip(r)
+                        .map(|(f, r)| (f - r).powi(2))
+                        .sum::<f64>()
+                        .sqrt()
+                })
+                .fold(f64::INFINITY, f64::min)
+        })
+        .sum::<f64>()
+        / reference.len() as f64
+}
+
+/// Multi-objective pso in the style of MOPSO, where every particle is led by
+/// a member of an archive of the non-dominated solutions found so far rather
+/// than by a neighbour. A personal best is replaced by a position which
+/// dominates it, or with probability a half when neither dominates the other.
+///
+/// Any velocity strategy can be used, with the leader given as the best
+/// particle and the whole swarm as the neighbourhood, so the topology of the
+/// settings is ignored. The velocity clamping, boundary policy and
+/// evaluation budget are those of the single objective pso, except that the
+/// problems are undefined outside their bounds so particles are clamped when
+/// there is no boundary policy, and are left unevaluated under the penalty
+/// policy. A budgeted run also stops after `IDLE` iterations in a row without
+/// an evaluation.
+pub fn mopso<const SIZE: usize>(
+    settings: &Settings,
+    bounds: &[Bound],
+    strategy: &(impl VelocityStrategy<SIZE> + ?Sized),
+    func: &impl Fn(&Vector<SIZE>) -> Vec<f64>,
+) -> Archive<SIZE> {
+    let settings = &Settings {
+        boundary: match settings.boundary {
+            Boundary::None => Boundary::Clamp,
+            boundary => boundary,
+        },
+        ..*settings
+    };
+    let mut rng = rand::thread_rng();
+    let mut archive = Archive::new(ARCHIVE);
+    let mut swarm = Vec::with_capacity(settings.particles);
+    let mut bests = Vec::with_capacity(settings.particles);
+    for _ in 0..settings.particles {
+        let particle = Particle::at(Vector::from(std::array::from_fn(|d| {
+            rng.gen_range(bounds[d].lower()..=bounds[d].upper())
+        })));
+        let objectives = func(&particle.coordinates());
+        archive.insert(particle.coordinates(), objectives.clone());
+        swarm.push(particle);
+        bests.push(objectives);
+    }
+
+    let mut evaluations = settings.particles;
+    let budget = settings.budget(SIZE);
+    let spent = |evaluations: usize| budget.is_some_and(|budget| evaluations >= budget);
+    let iterations = match budget {
+        Some(evaluations) => evaluations.saturating_sub(settings.particles) / settings.particles,
+        None => settings.iterations,
+    };
+
+    let mut iteration = 0;
+    // iterations in a row without an evaluation, which stop a budgeted run
+    // whose swarm stays outside the bounds
+    let mut idle = 0;
+    while !spent(evaluations) && idle < IDLE && (budget.is_some() || iteration < iterations) {
+        let before = evaluations;
+        let velocities = {
+            let neighbours = swarm.iter().collect::<Vec<_>>();
+            let context = Context {
+                iteration,
+                iterations,
+                neighbours: &neighbours,
+            };
+            swarm
+                .iter()
+                .zip(archive.leaders(swarm.len()))
+                .map(|(particle, leader)| {
+                    strategy.update(particle, &Particle::at(leader), &context)
+                })
+                .collect::<Vec<_>>()
+        };
+        for ((particle, best), v) in swarm.iter_mut().zip(&mut bests).zip(velocities) {
+            if spent(evaluations) {
+                break;
+            }
+            if particle.fly(v, bounds, settings) {
+                continue;
+            }
+
+            let objectives = func(&particle.coordinates());
+            evaluations += 1;
+            archive.insert(particle.coordinates(), objectives.clone());
+            if dominates(&objectives, best) || (!dominates(best, &objectives) && rng.gen_bool(0.5))
+            {
+                particle.remember();
+                *best = objectives;
+            }
+        }
+        iteration += 1;
+        if evaluations == before {
+            idle += 1;
+        } else {
+            idle = 0;
+        }
+    }
+
+    archive
+}
+
+/// An evolved velocity rule run by the multi-objective pso, whose output is
+/// the igd of the front it finds so that the evolver drives it to zero.
+pub struct Pareto<'a, const SIZE: usize>(pub Velocity<'a, SIZE>);
+
+impl<'a, const SIZE: usize> Grammar for Pareto<'a, SIZE> {
+    type Input = (&'a Problem<SIZE>, Settings);
+    type Output = f64;
+
+    fn run(&self, (problem, settings): &Self::Input) -> Self::Output {
+        let archive = mopso(settings, &problem.bounds, &self.0, &problem.func);
+        igd(&archive.objectives(), &problem.front)
+    }
+
+    fn generate(chromosome: &[u8]) -> Self {
+        Self(Velocity::generate(chromosome))
+    }
+}
+
+impl<'a, const SIZE: usize> Display for Pareto<'a, SIZE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<'a, const SIZE: usize> Expression for Pareto<'a, SIZE> {
+    fn size(&self) -> usize {
+        self.0.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Objectives of every archive member, in order.
+    fn members(archive: &Archive<1>) -> Vec<Vec<f64>> {
+        let mut members = archive
+            .objectives()
+            .into_iter()
+            .map(<[f64]>::to_vec)
+            .collect::<Vec<_>>();
+        members.sort_by(|x, y| x[0].total_cmp(&y[0]));
+        members
+    }
+
+    #[test]
+    fn hypervolume_2d() {
+        let front: [&[f64]; 3] = [&[1.0, 3.0], &[2.0, 2.0], &[3.0, 1.0]];
+        assert_eq!(hypervolume(&front, &[4.0, 4.0]), 6.0);
+        // dominated points and points beyond the reference add nothing
+        let front: [&[f64]; 5] = [
+            &[1.0, 3.0],
+            &[2.0, 2.0],
+            &[3.0, 1.0],
+            &[3.0, 3.0],
+            &[5.0, 0.0],
+        ];
+        assert_eq!(hypervolume(&front, &[4.0, 4.0]), 6.0);
+        assert_eq!(hypervolume(&[], &[4.0, 4.0]), 0.0);
+    }
+
+    #[test]
+    fn hypervolume_3d() {
+        assert_eq!(hypervolume(&[&[1.0, 1.0, 1.0]], &[2.0, 2.0, 2.0]), 1.0);
+        // boxes of 4 and 2 overlapping in a unit cube
+        let front: [&[f64]; 2] = [&[0.0, 0.0, 1.0], &[1.0, 1.0, 0.0]];
+        assert_eq!(hypervolume(&front, &[2.0, 2.0, 2.0]), 5.0);
+    }
+
+    #[test]
+    fn dominance() {
+        assert!(dominates(&[1.0, 2.0], &[1.0, 3.0]));
+        assert!(!dominates(&[1.0, 2.0], &[1.0, 2.0]));
+        assert!(!dominates(&[1.0, 3.0], &[2.0, 2.0]));
+
+        let mut archive = Archive::new(10);
+        archive.insert(Vector::from([0.0]), vec![2.0, 2.0]);
+        archive.insert(Vector::from([1.0]), vec![3.0, 3.0]);
+        archive.insert(Vector::from([2.0]), vec![2.0, 2.0]);
+        assert_eq!(members(&archive), vec![vec![2.0, 2.0]]);
+
+        archive.insert(Vector::from([3.0]), vec![1.0, 3.0]);
+        archive.insert(Vector::from([4.0]), vec![1.0, 1.0]);
+        assert_eq!(members(&archive), vec![vec![1.0, 1.0]]);
+    }
+
+    #[test]
+    fn truncation() {
+        // (2.5, 1.5) is the most crowded with a distance of 1 against the
+        // 1.25 of (2, 2), while the extremes are never crowded
+        let mut archive = Archive::new(3);
+        for (i, objectives) in [[0.0, 4.0], [2.0, 2.0], [2.5, 1.5], [4.0, 0.0]]
+            .into_iter()
+            .enumerate()
+        {
+            archive.insert(Vector::from([i as f64]), objectives.to_vec());
+        }
+        assert_eq!(
+            members(&archive),
+            vec![vec![0.0, 4.0], vec![2.0, 2.0], vec![4.0, 0.0]]
+        );
+        assert_eq!(
+            crowding(&[&[0.0, 4.0], &[2.0, 2.0], &[2.5, 1.5], &[4.0, 0.0]]),
+            vec![f64::INFINITY, 1.25, 1.0, f64::INFINITY]
+        );
+    }
+}
@@ -0,0 +1,383 @@
+use crate::FIXED;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+const STRATEGIES: [&str; 3] = ["canonical", "disposable", "reusable"];
+const COLOURS: [&str; 6] = [
+    "#1f77b4", "#d62728", "#2ca02c", "#ff7f0e", "#9467bd", "#8c564b",
+];
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 480.0;
+const LEFT: f64 = 80.0;
+const RIGHT: f64 = 20.0;
+const TOP: f64 = 40.0;
+const BOTTOM: f64 = 50.0;
+
+/// Renders a convergence plot and a box plot of the final fitness for every
+/// function found in `{input}/convergence`, comparing the canonical,
+/// disposable and reusable strategies of the given dimension. Functions only
+/// run at `FIXED` are plotted at that dimension instead, and functions
+/// without any runs are skipped.
+pub fn plot(input: &str, output: &str, size: usize) {
+    fs::create_dir_all(output).unwrap();
+
+    for function in 1..=functions(input, &STRATEGIES, size) {
+        let found = STRATEGIES
+            .iter()
+            .filter_map(|strategy| {
+                find_convergence(input, strategy, size, function)
+                    .map(|(dimension, runs)| (*strategy, dimension, runs))
+            })
+            .collect::<Vec<_>>();
+        let Some(&(_, dimension, _)) = found.first() else {
+            continue;
+        };
+        let runs = found
+            .into_iter()
+            .map(|(strategy, _, runs)| (strategy, runs))
+            .collect::<Vec<_>>();
+
+        let medians = runs
+            .iter()
+            .map(|(strategy, runs)| (*strategy, median_curve(runs)))
+            .collect::<Vec<_>>();
+        fs::write(
+            format!("{output}/convergence{size}_f{function}.svg"),
+            line_chart(
+                &format!("f{function} (D = {dimension})"),
+                "iteration",
+                "median best fitness",
+                &medians,
+            ),
+        )
+        .unwrap();
+
+        let finals = runs
+            .iter()
+            .map(|(strategy, runs)| {
+                (
+                    *strategy,
+                    runs.iter().filter_map(|run| run.last().copied()).collect(),
+                )
+            })
+            .collect::<Vec<_>>();
+        fs::write(
+            format!("{output}/box{size}_f{function}.svg"),
+            box_plot(
+                &format!("f{function} (D = {dimension})"),
+                "final best fitness",
+                &finals,
+            ),
+        )
+        .unwrap();
+    }
+}
+
+/// Highest function number with a convergence file of any of the strategies
+/// at `size` or at `FIXED`.
+fn functions(input: &str, strategies: &[&str], size: usize) -> usize {
+    let Ok(entries) = fs::read_dir(format!("{input}/convergence")) else {
+        return 0;
+    };
+
+    entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter_map(|file| {
+            let file = file.strip_suffix(".csv")?;
+            strategies.iter().find_map(|strategy| {
+                [size, FIXED].iter().find_map(|dimension| {
+                    file.strip_prefix(&format!("{strategy}{dimension}_f"))?
+                        .parse::<usize>()
+                        .ok()
+                })
+            })
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Runs of a strategy on a function at `size`, or at `FIXED` for functions
+/// which are only run at that dimension, along with the dimension found.
+pub fn find_convergence(
+    input: &str,
+    strategy: &str,
+    size: usize,
+    function: usize,
+) -> Option<(usize, Vec<Vec<f64>>)> {
+    [size, FIXED].into_iter().find_map(|dimension| {
+        let path = format!("{input}/convergence/{strategy}{dimension}_f{function}.csv");
+        read_convergence(Path::new(&path)).map(|runs| (dimension, runs))
+    })
+}
+
+/// Reads a convergence file written by `write_histories` into the best
+/// fitness per iteration of every run.
+pub fn read_convergence(path: &Path) -> Option<Vec<Vec<f64>>> {
+    let contents = fs::read_to_string(path).ok()?;
+    let mut runs: Vec<Vec<f64>> = Vec::new();
+
+    for line in contents.lines().skip(1) {
+        let mut fields = line.split(',').map(str::trim);
+        let (Some(run), Some(_), Some(best)) = (fields.next(), fields.next(), fields.next()) else {
+            continue;
+        };
+        let (Ok(run), Ok(best)) = (run.parse::<usize>(), best.parse::<f64>()) else {
+            continue;
+        };
+        if runs.len() <= run {
+            runs.resize(run + 1, Vec::new());
+        }
+        runs[run].push(best);
+    }
+
+    Some(runs)
+}
+
+fn median_curve(runs: &[Vec<f64>]) -> Vec<f64> {
+    let iterations = runs.iter().map(Vec::len).max().unwrap_or(0);
+    (0..iterations)
+        .map(|i| {
+            let values = runs
+                .iter()
+                .filter_map(|run| run.get(i).copied())
+                .collect::<Vec<_>>();
+            quantile(&values, 0.5)
+        })
+        .collect()
+}
+
+/// Linearly interpolated quantile of unsorted values.
+fn quantile(values: &[f64], q: f64) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|x, y| x.total_cmp(y));
+    let pos = q * (sorted.len() - 1) as f64;
+    let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+}
+
+/// Maps values onto the vertical pixel axis, using a log scale when every
+/// value is strictly positive and spans more than two orders of magnitude.
+struct Scale {
+    min: f64,
+    max: f64,
+    log: bool,
+}
+
+impl Scale {
+    fn new<'a>(values: impl Iterator<Item = &'a f64>) -> Self {
+        let values = values
+            .filter(|v| v.is_finite())
+            .copied()
+            .collect::<Vec<_>>();
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let log = min > 0.0 && max / min > 100.0;
+        let (min, max) = if log {
+            (min.log10(), max.log10())
+        } else {
+            (min, max)
+        };
+        let (min, max) = if min == max {
+            (min - 1.0, max + 1.0)
+        } else {
+            (min, max)
+        };
+
+        Self { min, max, log }
+    }
+
+    fn y(&self, value: f64) -> f64 {
+        let value = if self.log { value.log10() } else { value };
+        TOP + (HEIGHT - TOP - BOTTOM) * (1.0 - (value - self.min) / (self.max - self.min))
+    }
+
+    fn ticks(&self) -> Vec<(f64, String)> {
+        (0..=5)
+            .map(|i| {
+                let t = self.min + (self.max - self.min) * i as f64 / 5.0;
+                let value = if self.log { 10f64.powf(t) } else { t };
+                (self.y(value), format!("{value:.3e}"))
+            })
+            .collect()
+    }
+}
+
+fn header(svg: &mut String, title: &str, x_label: &str, y_label: &str, scale: &Scale) {
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" font-family="sans-serif" font-size="12">"#
+    )
+    .unwrap();
+    writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#).unwrap();
+    writeln!(
+        svg,
+        r#"<text x="{}" y="20" text-anchor="middle" font-size="14">{title}</text>"#,
+        WIDTH / 2.0
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<text x="{}" y="{}" text-anchor="middle">{x_label}</text>"#,
+        LEFT + (WIDTH - LEFT - RIGHT) / 2.0,
+        HEIGHT - 10.0
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<text x="15" y="{0}" text-anchor="middle" transform="rotate(-90 15 {0})">{y_label}{1}</text>"#,
+        TOP + (HEIGHT - TOP - BOTTOM) / 2.0,
+        if scale.log { " (log)" } else { "" }
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<polyline points="{LEFT},{TOP} {LEFT},{0} {1},{0}" fill="none" stroke="black"/>"#,
+        HEIGHT - BOTTOM,
+        WIDTH - RIGHT
+    )
+    .unwrap();
+    for (y, label) in scale.ticks() {
+        writeln!(
+            svg,
+            r#"<line x1="{0}" y1="{y}" x2="{LEFT}" y2="{y}" stroke="black"/><text x="{1}" y="{2}" text-anchor="end" font-size="10">{label}</text>"#,
+            LEFT - 4.0,
+            LEFT - 6.0,
+            y + 3.0
+        )
+        .unwrap();
+    }
+}
+
+fn line_chart(title: &str, x_label: &str, y_label: &str, series: &[(&str, Vec<f64>)]) -> String {
+    let scale = Scale::new(series.iter().flat_map(|(_, values)| values.iter()));
+    let iterations = series
+        .iter()
+        .map(|(_, values)| values.len())
+        .max()
+        .unwrap_or(1);
+    let x = |i: usize| LEFT + (WIDTH - LEFT - RIGHT) * i as f64 / (iterations.max(2) - 1) as f64;
+
+    let mut svg = String::new();
+    header(&mut svg, title, x_label, y_label, &scale);
+    writeln!(
+        svg,
+        r#"<text x="{}" y="{}" text-anchor="end" font-size="10">{}</text>"#,
+        WIDTH - RIGHT,
+        HEIGHT - BOTTOM + 14.0,
+        iterations
+    )
+    .unwrap();
+
+    for (i, (name, values)) in series.iter().enumerate() {
+        let colour = COLOURS[i % COLOURS.len()];
+        let points = values
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| v.is_finite() && (!scale.log || **v > 0.0))
+            .map(|(j, v)| format!("{:.2},{:.2}", x(j), scale.y(*v)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        writeln!(
+            svg,
+            r#"<polyline points="{points}" fill="none" stroke="{colour}" stroke-width="1.5"/>"#
+        )
+        .unwrap();
+        legend(&mut svg, i, name, colour);
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn box_plot(title: &str, y_label: &str, groups: &[(&str, Vec<f64>)]) -> String {
+    let scale = Scale::new(groups.iter().flat_map(|(_, values)| values.iter()));
+    let width = (WIDTH - LEFT - RIGHT) / groups.len() as f64;
+
+    let mut svg = String::new();
+    header(&mut svg, title, "strategy", y_label, &scale);
+
+    for (i, (name, values)) in groups.iter().enumerate() {
+        let values = values
+            .iter()
+            .copied()
+            .filter(|v| v.is_finite() && (!scale.log || *v > 0.0))
+            .collect::<Vec<_>>();
+        let centre = LEFT + width * (i as f64 + 0.5);
+        let colour = COLOURS[i % COLOURS.len()];
+        writeln!(
+            svg,
+            r#"<text x="{centre}" y="{}" text-anchor="middle">{name}</text>"#,
+            HEIGHT - BOTTOM + 16.0
+        )
+        .unwrap();
+        if values.is_empty() {
+            continue;
+        }
+
+        let (q1, median, q3) = (
+            quantile(&values, 0.25),
+            quantile(&values, 0.5),
+            quantile(&values, 0.75),
+        );
+        let iqr = q3 - q1;
+        let inside = values
+            .iter()
+            .copied()
+            .filter(|v| *v >= q1 - 1.5 * iqr && *v <= q3 + 1.5 * iqr);
+        let low = inside.clone().fold(f64::INFINITY, f64::min);
+        let high = inside.fold(f64::NEG_INFINITY, f64::max);
+        let half = width / 4.0;
+
+        writeln!(
+            svg,
+            r#"<line x1="{centre}" y1="{}" x2="{centre}" y2="{}" stroke="black"/>"#,
+            scale.y(low),
+            scale.y(high)
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{colour}" fill-opacity="0.4" stroke="black"/>"#,
+            centre - half,
+            scale.y(q3),
+            2.0 * half,
+            (scale.y(q1) - scale.y(q3)).max(1.0)
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<line x1="{0}" y1="{1}" x2="{2}" y2="{1}" stroke="black" stroke-width="2"/>"#,
+            centre - half,
+            scale.y(median),
+            centre + half
+        )
+        .unwrap();
+        for outlier in values.iter().filter(|v| **v < low || **v > high) {
+            writeln!(
+                svg,
+                r#"<circle cx="{centre}" cy="{}" r="2" fill="none" stroke="black"/>"#,
+                scale.y(*outlier)
+            )
+            .unwrap();
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn legend(svg: &mut String, i: usize, name: &str, colour: &str) {
+    let y = TOP + 10.0 + 16.0 * i as f64;
+    writeln!(
+        svg,
+        r#"<line x1="{0}" y1="{y}" x2="{1}" y2="{y}" stroke="{colour}" stroke-width="2"/><text x="{2}" y="{3}">{name}</text>"#,
+        WIDTH - RIGHT - 110.0,
+        WIDTH - RIGHT - 90.0,
+        WIDTH - RIGHT - 85.0,
+        y + 4.0
+    )
+    .unwrap();
+}
@@ -0,0 +1,455 @@
+use crate::function::Function;
+use mikes_pso::{bounds::Bound, vector::Vector};
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+/// Functions served by the external program run by `command`, which talks
+/// over its stdin and stdout one JSON object per line. The command is split
+/// into the program and its arguments by `words`.
+///
+/// The program is first sent `{"dimension": SIZE}` and answers with the
+/// functions it serves,
+///
+/// ```text
+/// {"functions": [{"name": "...", "bounds": [lower, upper], "minimum": 0}, ...]}
+/// ```
+///
+/// where `bounds` may instead hold a `[lower, upper]` pair for every
+/// dimension and a function only defined at its own dimension also gives it
+/// as `"dimension"`, which is then the number of pairs. After that every
+/// request `{"function": k, "positions": [[x1, ...], ...]}` asks for the
+/// fitness of a batch of positions of the `k`th function, indexed from zero,
+/// and is answered by `{"fitness": [f1, ...]}`, where a `null` fitness counts
+/// as infinitely bad. Any answer may instead be `{"error": "..."}`, which stops
+/// the experiment. The program is expected to exit when its stdin is closed.
+pub fn functions<const SIZE: usize>(command: &str) -> Vec<Function<SIZE>> {
+    let plugin = Arc::new(Mutex::new(Plugin::spawn(command)));
+    let handshake = plugin
+        .lock()
+        .unwrap()
+        .request(&format!("{{\"dimension\": {SIZE}}}"));
+    let Some(Json::Array(declarations)) = handshake.get("functions") else {
+        panic!("Expected the functions of {command}");
+    };
+
+    declarations
+        .iter()
+        .enumerate()
+        .map(|(k, declaration)| {
+            let name = match declaration.get("name") {
+                Some(Json::String(name)) => name.clone(),
+                _ => panic!("Expected the name of function {k} of {command}"),
+            };
+            let number = |json: &Json| match json {
+                Json::Number(x) => *x,
+                _ => panic!("Expected a number in the declaration of {name}"),
+            };
+            let dimension = declaration
+                .get("dimension")
+                .map(|dimension| number(dimension) as usize);
+            let bounds = match declaration.get("bounds") {
+                Some(Json::Array(bounds)) => match &bounds[..] {
+                    [Json::Number(lower), Json::Number(upper)] => {
+                        vec![Bound::from((*lower, *upper)); SIZE]
+                    }
+                    pairs => {
+                        let expected = dimension.unwrap_or(SIZE);
+                        if pairs.len() != expected {
+                            panic!(
+                                "Expected {expected} [lower, upper] bounds of {name}, got {}",
+                                pairs.len()
+                            );
+                        }
+                        let mut bounds = pairs
+                            .iter()
+                            .map(|pair| match pair {
+                                Json::Array(pair) if pair.len() == 2 => {
+                                    Bound::from((number(&pair[0]), number(&pair[1])))
+                                }
+                                _ => panic!("Expected [lower, upper] bounds of {name}"),
+                            })
+                            .collect::<Vec<_>>();
+                        // a function of another dimension is never run at
+                        // `SIZE`, but still has a bound for every coordinate
+                        if let Some(&last) = bounds.last() {
+                            bounds.resize(SIZE, last);
+                        }
+                        bounds
+                    }
+                },
+                _ => panic!("Expected the bounds of {name}"),
+            };
+
+            let single = Arc::clone(&plugin);
+            let plugin = Arc::clone(&plugin);
+            Function {
+                func: Box::new(move |coords: &Vector<SIZE>| {
+                    single
+                        .lock()
+                        .unwrap()
+                        .evaluate(k, std::slice::from_ref(coords))[0]
+                }),
+                batch: Some(Box::new(move |positions: &[Vector<SIZE>]| {
+                    plugin.lock().unwrap().evaluate(k, positions)
+                })),
+                truth: None,
+                minima: number(
+                    declaration
+                        .get("minimum")
+                        .unwrap_or_else(|| panic!("Expected the minimum of {name}")),
+                ),
+                optima: Vec::new(),
+                bounds,
+                dimension,
+                constraints: Vec::new(),
+                name,
+            }
+        })
+        .collect()
+}
+
+/// Splits `command` on whitespace outside of quotes, where single or double
+/// quotes group the characters between them into a word as they are, so
+/// that paths with spaces can be passed. There are no escapes and no shell
+/// expansions.
+fn words(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = None::<String>;
+    let mut quote = None;
+    for c in command.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => word.get_or_insert_with(String::new).push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            None if c.is_whitespace() => words.extend(word.take()),
+            None => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        panic!("Unterminated quote in {command}");
+    }
+    words.extend(word);
+
+    words
+}
+
+/// A running external program serving objective functions.
+pub struct Plugin {
+    command: String,
+    child: Child,
+    stdin: Option<ChildStdin>,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Plugin {
+    /// Starts `command`, split by `words` into the program and its
+    /// arguments.
+    fn spawn(command: &str) -> Self {
+        let words = words(command);
+        let (program, args) = words.split_first().expect("Expected a plugin command");
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap_or_else(|error| panic!("Could not start {command}: {error}"));
+
+        Self {
+            command: command.to_string(),
+            stdin: child.stdin.take(),
+            stdout: BufReader::new(child.stdout.take().unwrap()),
+            child,
+        }
+    }
+
+    /// Sends one line and parses the line answering it.
+    fn request(&mut self, line: &str) -> Json {
+        let command = &self.command;
+        let stdin = self.stdin.as_mut().unwrap();
+        writeln!(stdin, "{line}")
+            .and_then(|_| stdin.flush())
+            .unwrap_or_else(|error| panic!("Could not write to {command}: {error}"));
+
+        let mut answer = String::new();
+        match self.stdout.read_line(&mut answer) {
+            Ok(0) => panic!("{command} exited without answering"),
+            Ok(_) => (),
+            Err(error) => panic!("Could not read from {command}: {error}"),
+        }
+        let answer = Json::parse(&answer)
+            .unwrap_or_else(|error| panic!("Could not parse the answer of {command}: {error}"));
+        match answer.get("error") {
+            Some(Json::String(error)) => panic!("{command} failed: {error}"),
+            Some(error) => panic!("{command} failed: {error:?}"),
+            None => (),
+        }
+
+        answer
+    }
+
+    /// Fitness of every position for the `k`th function, in a single request.
+    pub fn evaluate<const SIZE: usize>(
+        &mut self,
+        k: usize,
+        positions: &[Vector<SIZE>],
+    ) -> Vec<f64> {
+        let mut line = format!("{{\"function\": {k}, \"positions\": [");
+        for (i, position) in positions.iter().enumerate() {
+            line.push_str(if i == 0 { "[" } else { ", [" });
+            for (d, x) in position.iter().enumerate() {
+                let separator = if d == 0 { "" } else { ", " };
+                // JSON has no infinities or NaN
+                if x.is_finite() {
+                    write!(line, "{separator}{x}").unwrap();
+                } else {
+                    write!(line, "{separator}null").unwrap();
+                }
+            }
+            line.push(']');
+        }
+        line.push_str("]}");
+
+        let command = self.command.clone();
+        match self.request(&line).get("fitness") {
+            Some(Json::Array(fitness)) if fitness.len() == positions.len() => fitness
+                .iter()
+                .map(|fitness| match fitness {
+                    Json::Number(fitness) => *fitness,
+                    Json::Null => f64::INFINITY,
+                    _ => panic!("Expected a number or null fitness from {command}"),
+                })
+                .collect(),
+            _ => panic!(
+                "Expected the fitness of {} positions from {command}",
+                positions.len()
+            ),
+        }
+    }
+}
+
+impl Drop for Plugin {
+    /// Closes the stdin of the program so that it exits, and waits for it.
+    fn drop(&mut self) {
+        self.stdin.take();
+        let _ = self.child.wait();
+    }
+}
+
+/// A JSON value of the protocol.
+#[derive(Debug, Clone, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Value of `key` when this is an object.
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn parse(source: &str) -> Result<Self, String> {
+        let mut chars = source.chars().peekable();
+        let value = Self::value(&mut chars)?;
+        skip_whitespace(&mut chars);
+        match chars.next() {
+            None => Ok(value),
+            Some(c) => Err(format!("unexpected `{c}` after the value")),
+        }
+    }
+
+    fn value(chars: &mut Chars) -> Result<Self, String> {
+        skip_whitespace(chars);
+        match chars.peek().copied() {
+            Some('{') => {
+                chars.next();
+                let mut members = Vec::new();
+                skip_whitespace(chars);
+                if chars.next_if_eq(&'}').is_some() {
+                    return Ok(Json::Object(members));
+                }
+                loop {
+                    skip_whitespace(chars);
+                    let Json::String(name) = Self::value(chars)? else {
+                        return Err("expected a member name".to_string());
+                    };
+                    skip_whitespace(chars);
+                    if chars.next() != Some(':') {
+                        return Err("expected `:`".to_string());
+                    }
+                    members.push((name, Self::value(chars)?));
+                    skip_whitespace(chars);
+                    match chars.next() {
+                        Some(',') => (),
+                        Some('}') => return Ok(Json::Object(members)),
+                        _ => return Err("expected `,` or `}`".to_string()),
+                    }
+                }
+            }
+            Some('[') => {
+                chars.next();
+                let mut elements = Vec::new();
+                skip_whitespace(chars);
+                if chars.next_if_eq(&']').is_some() {
+                    return Ok(Json::Array(elements));
+                }
+                loop {
+                    elements.push(Self::value(chars)?);
+                    skip_whitespace(chars);
+                    match chars.next() {
+                        Some(',') => (),
+                        Some(']') => return Ok(Json::Array(elements)),
+                        _ => return Err("expected `,` or `]`".to_string()),
+                    }
+                }
+            }
+            Some('"') => {
+                chars.next();
+                let mut string = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => return Ok(Json::String(string)),
+                        Some('\\') => string.push(match chars.next() {
+                            Some('n') => '\n',
+                            Some('t') => '\t',
+                            Some('r') => '\r',
+                            Some('b') => '\u{8}',
+                            Some('f') => '\u{c}',
+                            Some('u') => {
+                                let code = chars.by_ref().take(4).collect::<String>();
+                                u32::from_str_radix(&code, 16)
+                                    .ok()
+                                    .and_then(char::from_u32)
+                                    .unwrap_or(char::REPLACEMENT_CHARACTER)
+                            }
+                            Some(c) => c,
+                            None => return Err("unterminated string".to_string()),
+                        }),
+                        Some(c) => string.push(c),
+                        None => return Err("unterminated string".to_string()),
+                    }
+                }
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let mut number = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || "+-.eE".contains(*c)) {
+                    number.push(c);
+                }
+                number
+                    .parse()
+                    .map(Json::Number)
+                    .map_err(|_| format!("invalid number `{number}`"))
+            }
+            Some(c) if c.is_alphabetic() => {
+                let mut word = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_alphabetic()) {
+                    word.push(c);
+                }
+                match word.as_str() {
+                    "null" => Ok(Json::Null),
+                    "true" => Ok(Json::Bool(true)),
+                    "false" => Ok(Json::Bool(false)),
+                    _ => Err(format!("unexpected `{word}`")),
+                }
+            }
+            Some(c) => Err(format!("unexpected `{c}`")),
+            None => Err("unexpected end of line".to_string()),
+        }
+    }
+}
+
+type Chars<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+
+fn skip_whitespace(chars: &mut Chars) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands() {
+        assert_eq!(
+            words("  python3 serve.py  --fast "),
+            ["python3", "serve.py", "--fast"]
+        );
+        assert_eq!(
+            words(r#"python3 "my plugins/serve.py" --name 'a "b"'"#),
+            ["python3", "my plugins/serve.py", "--name", r#"a "b""#]
+        );
+        // quotes join with the rest of a word and may be empty
+        assert_eq!(words(r#"--path=/a" "b '' x"#), ["--path=/a b", "", "x"]);
+        assert!(words("").is_empty());
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(
+            Json::parse(r#""a\nb\t\"\\\/é""#),
+            Ok(Json::String("a\nb\t\"\\/é".to_string()))
+        );
+        assert_eq!(
+            Json::parse(r#""\uzzzz""#),
+            Ok(Json::String(char::REPLACEMENT_CHARACTER.to_string()))
+        );
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(Json::parse("0"), Ok(Json::Number(0.0)));
+        assert_eq!(Json::parse("-1.5e3"), Ok(Json::Number(-1500.0)));
+        assert_eq!(Json::parse("1E-2"), Ok(Json::Number(0.01)));
+        assert_eq!(Json::parse("2e+1"), Ok(Json::Number(20.0)));
+        assert!(Json::parse("1.2.3").is_err());
+        assert!(Json::parse("-").is_err());
+    }
+
+    #[test]
+    fn nesting() {
+        let json = Json::parse(r#" {"a": [1, {"b": null}], "c": true, "d": {}, "e": []} "#);
+        assert_eq!(
+            json,
+            Ok(Json::Object(vec![
+                (
+                    "a".to_string(),
+                    Json::Array(vec![
+                        Json::Number(1.0),
+                        Json::Object(vec![("b".to_string(), Json::Null)]),
+                    ])
+                ),
+                ("c".to_string(), Json::Bool(true)),
+                ("d".to_string(), Json::Object(Vec::new())),
+                ("e".to_string(), Json::Array(Vec::new())),
+            ]))
+        );
+        assert_eq!(json.unwrap().get("c"), Some(&Json::Bool(true)));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(Json::parse("").is_err());
+        assert!(Json::parse(r#""unterminated"#).is_err());
+        assert!(Json::parse("[1,]").is_err());
+        assert!(Json::parse("[1 2]").is_err());
+        assert!(Json::parse(r#"{"a" 1}"#).is_err());
+        assert!(Json::parse(r#"{1: 2}"#).is_err());
+        assert!(Json::parse("nul").is_err());
+        assert!(Json::parse("1 2").is_err());
+    }
+}
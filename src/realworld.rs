@@ -0,0 +1,185 @@
+use crate::function::Function;
+use mikes_pso::{bounds::Bound, vector::Vector};
+use std::f64::consts::PI;
+
+/// Number of atoms of the Lennard-Jones cluster.
+const ATOMS: usize = 10;
+
+/// Number of hidden units of the neural network.
+const HIDDEN: usize = 6;
+
+/// Evaluates `func` on the coordinates as a slice so that the problems can
+/// be written at their own dimension.
+fn sliced<const SIZE: usize>(
+    func: impl Fn(&[f64]) -> f64 + Send + 'static,
+) -> Box<dyn Fn(&Vector<SIZE>) -> f64 + Send> {
+    Box::new(move |coords: &Vector<SIZE>| func(&coords.iter().copied().collect::<Vec<_>>()))
+}
+
+fn problem<const SIZE: usize>(
+    name: &str,
+    func: impl Fn(&[f64]) -> f64 + Send + 'static,
+    minima: f64,
+    optima: &[&[f64]],
+    bounds: Vec<(f64, f64)>,
+) -> Function<SIZE> {
+    Function {
+        name: name.to_string(),
+        func: sliced(func),
+        batch: None,
+        truth: None,
+        minima,
+        optima: optima
+            .iter()
+            .map(|optimum| {
+                Vector::from(std::array::from_fn(|d| {
+                    optimum.get(d).copied().unwrap_or(0.0)
+                }))
+            })
+            .collect(),
+        dimension: Some(bounds.len()),
+        bounds: bounds.into_iter().map(Bound::from).collect(),
+        constraints: Vec::new(),
+    }
+}
+
+/// Deterministic real-world problems from the CEC 2011 competition and a
+/// neural network fit, each only defined at its own dimension so that only
+/// those which fit `SIZE` can be run. The minima of the Lennard-Jones cluster
+/// and the radar code are the best known, and that of the neural network is
+/// a perfect fit.
+pub fn functions<const SIZE: usize>() -> Vec<Function<SIZE>> {
+    let target = wave(&TARGET);
+    let data = spirals();
+
+    vec![
+        problem(
+            "lennard-jones",
+            lennard_jones,
+            -28.422532,
+            // the optimum is only unique up to rotation and translation
+            &[],
+            vec![(-4.0, 4.0); 3 * ATOMS],
+        ),
+        problem(
+            "fm sound wave",
+            move |x| {
+                wave(x)
+                    .iter()
+                    .zip(&target)
+                    .map(|(y, y0)| (y - y0).powi(2))
+                    .sum::<f64>()
+            },
+            0.0,
+            &[&TARGET],
+            vec![(-6.4, 6.35); 6],
+        ),
+        problem(
+            "radar polyphase code",
+            radar,
+            0.5,
+            &[],
+            vec![(0.0, 2.0 * PI); 20],
+        ),
+        problem(
+            "two spirals network",
+            move |x| network(x, &data),
+            0.0,
+            &[],
+            vec![(-10.0, 10.0); 4 * HIDDEN + 1],
+        ),
+    ]
+}
+
+/// Potential energy of a cluster of atoms whose positions are consecutive
+/// triples of coordinates, in the reduced units where every pair contributes
+/// `r^-12 - 2 r^-6`.
+fn lennard_jones(x: &[f64]) -> f64 {
+    let atoms = x.chunks(3).collect::<Vec<_>>();
+    let mut energy = 0.0;
+    for i in 0..atoms.len() {
+        for j in i + 1..atoms.len() {
+            let r2 = (0..3)
+                .map(|d| (atoms[i][d] - atoms[j][d]).powi(2))
+                .sum::<f64>();
+            let r6 = r2.powi(3);
+            energy += 1.0 / (r6 * r6) - 2.0 / r6;
+        }
+    }
+
+    energy
+}
+
+/// Amplitudes and frequencies `(a1, w1, a2, w2, a3, w3)` of the target wave.
+const TARGET: [f64; 6] = [1.0, 5.0, -1.5, 4.8, 2.0, 4.9];
+
+/// Samples of the frequency modulated wave
+/// `a1 sin(w1 t theta + a2 sin(w2 t theta + a3 sin(w3 t theta)))` with
+/// `theta = 2 pi / 100` at `t = 0, ..., 100`.
+fn wave(x: &[f64]) -> Vec<f64> {
+    let theta = 2.0 * PI / 100.0;
+    (0..=100)
+        .map(|t| {
+            let t = t as f64 * theta;
+            x[0] * (x[1] * t + x[2] * (x[3] * t + x[4] * (x[5] * t).sin()).sin()).sin()
+        })
+        .collect()
+}
+
+/// Largest autocorrelation of the polyphase pulse whose phase differences
+/// are `x`, which the radar minimises.
+fn radar(x: &[f64]) -> f64 {
+    let d = x.len();
+    // sum of the phases from `from` to `to`, one indexed and inclusive
+    let phase = |from: usize, to: usize| x[from - 1..to].iter().sum::<f64>();
+    let mut largest = f64::MIN;
+    for i in 1..=d {
+        let odd = (i..=d)
+            .map(|j| phase((2 * i).abs_diff(j + 1) + 1, j).cos())
+            .sum::<f64>();
+        largest = largest.max(odd.abs());
+        if i < d {
+            let even = 0.5
+                + (i + 1..=d)
+                    .map(|j| phase((2 * i).abs_diff(j) + 1, j).cos())
+                    .sum::<f64>();
+            largest = largest.max(even.abs());
+        }
+    }
+
+    largest
+}
+
+/// The two spirals classification dataset of Lang and Witbrock, 97 points
+/// on each spiral with the first labelled one.
+fn spirals() -> Vec<([f64; 2], f64)> {
+    (0..97)
+        .flat_map(|i| {
+            let angle = i as f64 * PI / 16.0;
+            let radius = 6.5 * (104 - i) as f64 / 104.0;
+            let (x, y) = (radius * angle.sin(), radius * angle.cos());
+            [([x, y], 1.0), ([-x, -y], 0.0)]
+        })
+        .collect()
+}
+
+/// Mean squared error on the two spirals of a network with `HIDDEN` tanh
+/// units and a sigmoid output, whose weights are laid out as the input
+/// weights and bias of every hidden unit followed by the output weights and
+/// bias.
+fn network(x: &[f64], data: &[([f64; 2], f64)]) -> f64 {
+    let (hidden, output) = x.split_at(3 * HIDDEN);
+    data.iter()
+        .map(|(input, label)| {
+            let activation = hidden
+                .chunks(3)
+                .zip(output)
+                .map(|(w, v)| v * (w[0] * input[0] + w[1] * input[1] + w[2]).tanh())
+                .sum::<f64>()
+                + output[HIDDEN];
+            let prediction = 1.0 / (1.0 + (-activation).exp());
+            (prediction - label).powi(2)
+        })
+        .sum::<f64>()
+        / data.len() as f64
+}
@@ -0,0 +1,334 @@
+use crate::plot::find_convergence;
+use std::fmt::Write as _;
+use std::fs;
+
+/// Significance level of the rank-sum test against the best strategy.
+const ALPHA: f64 = 0.05;
+
+/// Mean and standard deviation of a strategy on one function.
+struct Cell {
+    mean: f64,
+    std: f64,
+    /// final fitness of every run, used for the significance test
+    runs: Option<Vec<f64>>,
+}
+
+struct Row {
+    name: String,
+    cells: Vec<Option<Cell>>,
+}
+
+/// Joins the `{input}/{strategy}{size}.csv` results of every strategy on the
+/// function name into a single table per dimension written as `{output}/table{size}.tex` and
+/// `{output}/table{size}.md`.
+///
+/// The best mean of each row is bold and a dagger marks strategies which are
+/// significantly worse than the best according to a Wilcoxon rank-sum test on
+/// the final fitness of every run found in `{input}/convergence`.
+pub fn report(input: &str, output: &str, sizes: &[usize], strategies: &[&str]) {
+    fs::create_dir_all(output).unwrap();
+
+    for &size in sizes {
+        let rows = read_rows(input, size, strategies);
+        if rows.is_empty() {
+            continue;
+        }
+        fs::write(
+            format!("{output}/table{size}.tex"),
+            latex(&rows, size, strategies),
+        )
+        .unwrap();
+        fs::write(
+            format!("{output}/table{size}.md"),
+            markdown(&rows, size, strategies),
+        )
+        .unwrap();
+    }
+}
+
+fn read_rows(input: &str, size: usize, strategies: &[&str]) -> Vec<Row> {
+    let mut rows: Vec<Row> = Vec::new();
+
+    for (s, strategy) in strategies.iter().enumerate() {
+        let Ok(contents) = fs::read_to_string(format!("{input}/{strategy}{size}.csv")) else {
+            continue;
+        };
+        let mut lines = contents.lines();
+        // older results were written without the function column, and their
+        // functions are only named by their position
+        let named = lines
+            .next()
+            .is_some_and(|header| header.trim_start().starts_with("function"));
+
+        for (i, line) in lines.filter(|line| !line.trim().is_empty()).enumerate() {
+            let mut fields = line.split(',').map(str::trim).collect::<Vec<_>>();
+            let name = if named {
+                fields.remove(0).to_string()
+            } else {
+                format!("f{}", i + 1)
+            };
+            let (Some(Ok(mean)), Some(Ok(std))) = (
+                fields.get(1).map(|f| f.parse::<f64>()),
+                fields.get(2).map(|f| f.parse::<f64>()),
+            ) else {
+                continue;
+            };
+
+            let row = match rows.iter().position(|row| row.name == name) {
+                Some(row) => &mut rows[row],
+                None => {
+                    rows.push(Row {
+                        name,
+                        cells: Vec::new(),
+                    });
+                    rows.last_mut().unwrap()
+                }
+            };
+            row.cells.resize_with(strategies.len(), || None);
+            row.cells[s] = Some(Cell {
+                mean,
+                std,
+                runs: find_convergence(input, strategy, size, i + 1)
+                    .map(|(_, runs)| runs.iter().filter_map(|run| run.last().copied()).collect()),
+            });
+        }
+    }
+
+    rows
+}
+
+impl Row {
+    /// Index of the strategy with the lowest mean.
+    fn best(&self) -> Option<usize> {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter_map(|(i, cell)| cell.as_ref().map(|cell| (i, cell.mean)))
+            .filter(|(_, mean)| !mean.is_nan())
+            .min_by(|(_, x), (_, y)| x.total_cmp(y))
+            .map(|(i, _)| i)
+    }
+
+    /// Whether the strategy is significantly worse than the best strategy.
+    fn worse(&self, i: usize, best: usize) -> bool {
+        let (Some(Some(x)), Some(Some(y))) = (
+            self.cells[i].as_ref().map(|cell| cell.runs.as_ref()),
+            self.cells[best].as_ref().map(|cell| cell.runs.as_ref()),
+        ) else {
+            return false;
+        };
+
+        i != best && rank_sum(x, y) < ALPHA
+    }
+}
+
+/// Formats to four decimals, switching to scientific notation for very
+/// small or large magnitudes.
+fn format(x: f64) -> String {
+    if x == 0.0 || (1e-4..1e4).contains(&x.abs()) {
+        format!("{x:.4}")
+    } else {
+        format!("{x:.4e}")
+    }
+}
+
+fn latex(rows: &[Row], size: usize, strategies: &[&str]) -> String {
+    let mut tex = String::new();
+    writeln!(tex, "\\begin{{table}}[ht]").unwrap();
+    writeln!(tex, "\\centering").unwrap();
+    writeln!(
+        tex,
+        "\\begin{{tabular}}{{l{}}}",
+        "c".repeat(strategies.len())
+    )
+    .unwrap();
+    writeln!(tex, "\\hline").unwrap();
+    writeln!(
+        tex,
+        "Function & {} \\\\",
+        strategies
+            .iter()
+            .map(|strategy| escape_latex(strategy))
+            .collect::<Vec<_>>()
+            .join(" & ")
+    )
+    .unwrap();
+    writeln!(tex, "\\hline").unwrap();
+
+    for row in rows {
+        let best = row.best();
+        let cells = row
+            .cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| {
+                let Some(cell) = cell else {
+                    return "-".to_string();
+                };
+                let mut text = format!("{} $\\pm$ {}", format(cell.mean), format(cell.std));
+                if best == Some(i) {
+                    text = format!("\\textbf{{{text}}}");
+                }
+                if best.is_some_and(|best| row.worse(i, best)) {
+                    text.push_str("$^\\dagger$");
+                }
+                text
+            })
+            .collect::<Vec<_>>();
+        writeln!(
+            tex,
+            "{} & {} \\\\",
+            escape_latex(&row.name),
+            cells.join(" & ")
+        )
+        .unwrap();
+    }
+
+    writeln!(tex, "\\hline").unwrap();
+    writeln!(tex, "\\end{{tabular}}").unwrap();
+    writeln!(
+        tex,
+        "\\caption{{Mean $\\pm$ standard deviation for $D = {size}$. $^\\dagger$ significantly worse than the best ($p < {ALPHA}$).}}"
+    )
+    .unwrap();
+    writeln!(tex, "\\end{{table}}").unwrap();
+
+    tex
+}
+
+fn markdown(rows: &[Row], size: usize, strategies: &[&str]) -> String {
+    let mut md = String::new();
+    writeln!(
+        md,
+        "| Function | {} |",
+        strategies
+            .iter()
+            .map(|strategy| escape_markdown(strategy))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    )
+    .unwrap();
+    writeln!(md, "|---|{}", "---|".repeat(strategies.len())).unwrap();
+
+    for row in rows {
+        let best = row.best();
+        let cells = row
+            .cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| {
+                let Some(cell) = cell else {
+                    return "-".to_string();
+                };
+                let mut text = format!("{} ± {}", format(cell.mean), format(cell.std));
+                if best == Some(i) {
+                    text = format!("**{text}**");
+                }
+                if best.is_some_and(|best| row.worse(i, best)) {
+                    text.push_str(" †");
+                }
+                text
+            })
+            .collect::<Vec<_>>();
+        writeln!(
+            md,
+            "| {} | {} |",
+            escape_markdown(&row.name),
+            cells.join(" | ")
+        )
+        .unwrap();
+    }
+
+    writeln!(
+        md,
+        "\nMean ± standard deviation for D = {size}. † significantly worse than the best (p < {ALPHA})."
+    )
+    .unwrap();
+
+    md
+}
+
+/// Escapes the characters of a name which LaTeX treats specially in a table.
+fn escape_latex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '_' | '&' | '%' | '#' | '$' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '|' => escaped.push_str("\\textbar{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Escapes the characters of a name which markdown treats specially in a
+/// table.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '_' | '|' | '#' | '%' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '&' => escaped.push_str("&amp;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Two sided p-value of the Wilcoxon rank-sum (Mann-Whitney U) test using
+/// the normal approximation with average ranks for ties.
+fn rank_sum(x: &[f64], y: &[f64]) -> f64 {
+    if x.is_empty() || y.is_empty() {
+        return 1.0;
+    }
+
+    let mut all = x
+        .iter()
+        .map(|v| (*v, true))
+        .chain(y.iter().map(|v| (*v, false)))
+        .collect::<Vec<_>>();
+    all.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut rank_x = 0.0;
+    let mut i = 0;
+    while i < all.len() {
+        let mut j = i;
+        while j + 1 < all.len() && all[j + 1].0 == all[i].0 {
+            j += 1;
+        }
+        let rank = (i + j) as f64 / 2.0 + 1.0;
+        rank_x += all[i..=j].iter().filter(|(_, from_x)| *from_x).count() as f64 * rank;
+        i = j + 1;
+    }
+
+    let (n, m) = (x.len() as f64, y.len() as f64);
+    let u = rank_x - n * (n + 1.0) / 2.0;
+    let sd = (n * m * (n + m + 1.0) / 12.0).sqrt();
+    if sd == 0.0 {
+        return 1.0;
+    }
+    let z = (u - n * m / 2.0) / sd;
+
+    erfc(z.abs() / std::f64::consts::SQRT_2)
+}
+
+/// Complementary error function (Abramowitz and Stegun 7.1.26).
+fn erfc(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+
+    poly * (-x * x).exp()
+}
@@ -0,0 +1,102 @@
+use crate::swarm::Particle;
+use mikes_pso::vector::Vector;
+use rand::Rng;
+
+/// Progress of the swarm, passed to every velocity update.
+#[derive(Debug, Clone, Copy)]
+pub struct Context<'a, const SIZE: usize> {
+    pub iteration: usize,
+    pub iterations: usize,
+    /// the neighbourhood of the particle, including itself, before any
+    /// particle of this iteration has moved
+    pub neighbours: &'a [&'a Particle<SIZE>],
+}
+
+/// A velocity update rule which can be plugged into the pso.
+pub trait VelocityStrategy<const SIZE: usize> {
+    fn name(&self) -> String;
+
+    /// Named parameters of the rule, used to label results.
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        Vec::new()
+    }
+
+    /// Whether the rule reads the whole neighbourhood of the context, which
+    /// only the swarm of this crate gives, so that it never runs in
+    /// `mikes_pso::pso::pso`.
+    fn informed(&self) -> bool {
+        false
+    }
+
+    fn update(
+        &self,
+        current: &Particle<SIZE>,
+        best: &Particle<SIZE>,
+        context: &Context<SIZE>,
+    ) -> Vector<SIZE>;
+}
+
+/// How the random numbers of the cognitive and social terms are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Random {
+    /// a new pair for every dimension, as in the textbook definition
+    PerDimension,
+    /// a single pair shared by every dimension of a particle
+    PerParticle,
+}
+
+/// The canonical inertia weight pso
+/// `w v + c1 r1 (p - x) + c2 r2 (g - x)`, whose velocity is clamped like that
+/// of every other strategy by the settings of the pso.
+#[derive(Debug, Clone, Copy)]
+pub struct Canonical {
+    pub w: f64,
+    pub c1: f64,
+    pub c2: f64,
+    pub random: Random,
+}
+
+impl Default for Canonical {
+    fn default() -> Self {
+        Self {
+            w: 0.4,
+            c1: 2.0,
+            c2: 2.0,
+            random: Random::PerDimension,
+        }
+    }
+}
+
+impl<const SIZE: usize> VelocityStrategy<SIZE> for Canonical {
+    fn name(&self) -> String {
+        "canonical".to_string()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        vec![("w", self.w), ("c1", self.c1), ("c2", self.c2)]
+    }
+
+    fn update(
+        &self,
+        current: &Particle<SIZE>,
+        best: &Particle<SIZE>,
+        _: &Context<SIZE>,
+    ) -> Vector<SIZE> {
+        let mut rng = rand::thread_rng();
+        let (x, v, p, g) = (
+            current.coordinates(),
+            current.velocity(),
+            current.best(),
+            best.best(),
+        );
+        let (r1, r2): (f64, f64) = rng.gen();
+
+        Vector::from(std::array::from_fn(|d| {
+            let (r1, r2) = match self.random {
+                Random::PerDimension => rng.gen(),
+                Random::PerParticle => (r1, r2),
+            };
+            self.w * v[d] + self.c1 * r1 * (p[d] - x[d]) + self.c2 * r2 * (g[d] - x[d])
+        }))
+    }
+}
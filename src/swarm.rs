@@ -0,0 +1,847 @@
+use crate::function::{Counted, Function};
+use crate::strategy::{Context, VelocityStrategy};
+use crate::topology::Topology;
+use mikes_pso::{bounds::Bound, vector::Vector};
+use rand::Rng;
+use std::cell::RefCell;
+
+/// Number of consecutive iterations without a single evaluation after which
+/// a run with an evaluation budget stops, as a swarm kept outside the bounds
+/// under the penalty policy would otherwise never spend its budget.
+pub const IDLE: usize = 100;
+
+/// Relative difference in the fitness of the change detection sentinel above
+/// which the function is taken to have changed. Noise larger than this is
+/// read as a change, so detection is only meant for noiseless functions.
+pub const TOLERANCE: f64 = 1e-9;
+
+/// What happens to a particle component which leaves its bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Boundary {
+    /// the particle is free to leave the bounds
+    None,
+    /// the component is moved back onto the bound and its velocity zeroed
+    Clamp,
+    /// the component is mirrored back into the bounds and its velocity negated
+    Reflect,
+    /// the component is reinitialised uniformly within the bounds
+    Reinitialise,
+    /// the particle is given an infinite fitness while outside the bounds
+    Penalty,
+}
+
+/// How a particle moves by its velocity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Movement {
+    /// the velocity is added to the position
+    Continuous,
+    /// every component is set to its upper bound with probability
+    /// `sigmoid(v)` and to its lower bound otherwise, as in the binary pso of
+    /// Kennedy and Eberhart
+    Binary,
+}
+
+/// How particles are compared on constrained functions, where the violation
+/// of a particle is the total amount by which it breaks the constraints.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Handling {
+    /// the violation times the coefficient is added to the fitness
+    Penalty(f64),
+    /// Deb's feasibility rules, where a feasible particle beats an infeasible
+    /// one, two feasible particles are compared by fitness and two infeasible
+    /// ones by violation
+    Feasibility,
+    /// the feasibility rules with every violation up to `epsilon` counted as
+    /// feasible, where `epsilon` starts at the given level and shrinks as
+    /// `(1 - t / 0.8T)^5` to zero after 80% of the iterations
+    Epsilon(f64),
+}
+
+impl Handling {
+    /// Whether `(fitness, violation)` `x` is better than `y` at `iteration`
+    /// of `iterations`.
+    pub fn better(
+        &self,
+        x: (f64, f64),
+        y: (f64, f64),
+        iteration: usize,
+        iterations: usize,
+    ) -> bool {
+        let epsilon = match *self {
+            Handling::Penalty(coefficient) => {
+                return x.0 + coefficient * x.1 < y.0 + coefficient * y.1;
+            }
+            Handling::Feasibility => 0.0,
+            Handling::Epsilon(level) => {
+                let progress = iteration as f64 / (0.8 * iterations as f64);
+                level * (1.0 - progress.min(1.0)).powi(5)
+            }
+        };
+        let violation = |v: f64| if v <= epsilon { 0.0 } else { v };
+        let (vx, vy) = (violation(x.1), violation(y.1));
+
+        if vx == vy {
+            x.0 < y.0
+        } else {
+            vx < vy
+        }
+    }
+}
+
+/// Settings of a single pso run.
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+    pub particles: usize,
+    pub iterations: usize,
+    /// maximum number of function evaluations, which replaces `iterations`
+    /// as the stopping condition when set
+    pub evaluations: Option<usize>,
+    /// maximum number of function evaluations per dimension of the function,
+    /// used as the budget when `evaluations` is not set
+    pub evaluations_per_dimension: Option<usize>,
+    /// maximum velocity per dimension as a fraction of the bound range
+    pub vmax: Option<f64>,
+    pub boundary: Boundary,
+    pub movement: Movement,
+    pub topology: Topology,
+    /// comparison of particles on constrained functions
+    pub handling: Handling,
+    /// whether a fixed sentinel position is re-evaluated every iteration to
+    /// detect changes of a dynamic function, after which the best position of
+    /// every particle is evaluated again or forgotten
+    pub detection: bool,
+    /// whether the swarm diversity is recorded in the history
+    pub diversity: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            particles: 100,
+            iterations: 100,
+            evaluations: None,
+            evaluations_per_dimension: None,
+            vmax: None,
+            boundary: Boundary::None,
+            movement: Movement::Continuous,
+            topology: Topology::Star,
+            handling: Handling::Feasibility,
+            detection: false,
+            diversity: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Maximum number of function evaluations of a run on a function of the
+    /// given dimension, if any.
+    pub fn budget(&self, dimension: usize) -> Option<usize> {
+        self.evaluations
+            .or_else(|| Some(self.evaluations_per_dimension? * dimension))
+    }
+
+    /// Whether `mikes_pso::pso::pso` can run with these settings, which only
+    /// holds for an unclamped continuous swarm in the star topology without a
+    /// boundary policy or change detection.
+    pub fn upstream(&self) -> bool {
+        self.vmax.is_none()
+            && self.boundary == Boundary::None
+            && self.movement == Movement::Continuous
+            && self.topology == Topology::Star
+            && !self.detection
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Particle<const SIZE: usize> {
+    coordinates: Vector<SIZE>,
+    velocity: Vector<SIZE>,
+    best: Vector<SIZE>,
+    fitness: f64,
+    best_fitness: f64,
+    violation: f64,
+    best_violation: f64,
+}
+
+impl<const SIZE: usize> Particle<SIZE> {
+    /// A position drawn uniformly within the bounds, or on either bound
+    /// under binary movement.
+    fn place(bounds: &[Bound], settings: &Settings) -> Vector<SIZE> {
+        let mut rng = rand::thread_rng();
+        Vector::from(std::array::from_fn(|i| {
+            let (lower, upper) = (bounds[i].lower(), bounds[i].upper());
+            match settings.movement {
+                Movement::Continuous => rng.gen_range(lower..=upper),
+                Movement::Binary if rng.gen_bool(0.5) => upper,
+                Movement::Binary => lower,
+            }
+        }))
+    }
+
+    /// A particle at rest at `coordinates` with the given fitness and
+    /// violation.
+    fn new(coordinates: Vector<SIZE>, (fitness, violation): (f64, f64)) -> Self {
+        Self {
+            coordinates,
+            velocity: Vector::from([0.0; SIZE]),
+            best: coordinates,
+            fitness,
+            best_fitness: fitness,
+            violation,
+            best_violation: violation,
+        }
+    }
+
+    pub fn coordinates(&self) -> Vector<SIZE> {
+        self.coordinates
+    }
+
+    pub fn velocity(&self) -> Vector<SIZE> {
+        self.velocity
+    }
+
+    pub fn best(&self) -> Vector<SIZE> {
+        self.best
+    }
+
+    /// A particle at rest at `coordinates` which remembers them as its best,
+    /// for swarms which keep track of fitness themselves.
+    pub fn at(coordinates: Vector<SIZE>) -> Self {
+        Self {
+            coordinates,
+            velocity: Vector::from([0.0; SIZE]),
+            best: coordinates,
+            fitness: f64::NAN,
+            best_fitness: f64::NAN,
+            violation: f64::NAN,
+            best_violation: f64::NAN,
+        }
+    }
+
+    /// A particle of `mikes_pso::pso::pso` as seen by a velocity strategy,
+    /// without the fitness it keeps to itself.
+    fn upstream(particle: &mikes_pso::particle::Particle<SIZE>) -> Self {
+        Self {
+            velocity: particle.velocity(),
+            best: particle.best(),
+            ..Self::at(particle.coordinates())
+        }
+    }
+
+    /// Remembers the current coordinates as the best ones.
+    pub fn remember(&mut self) {
+        self.best = self.coordinates;
+    }
+
+    /// Moves the particle by `velocity` under the velocity clamping,
+    /// movement and boundary policy of the settings, returning whether it is
+    /// outside the bounds under the penalty policy. Binary movement never
+    /// leaves the bounds.
+    pub fn fly(&mut self, velocity: Vector<SIZE>, bounds: &[Bound], settings: &Settings) -> bool {
+        let mut rng = rand::thread_rng();
+        let mut outside = false;
+        let mut v: [f64; SIZE] = std::array::from_fn(|d| velocity[d]);
+        let x: [f64; SIZE] = std::array::from_fn(|d| {
+            let (lower, upper) = (bounds[d].lower(), bounds[d].upper());
+            if let Some(vmax) = settings.vmax {
+                let vmax = vmax * (upper - lower);
+                v[d] = v[d].clamp(-vmax, vmax);
+            }
+            if settings.movement == Movement::Binary {
+                let sigmoid = 1.0 / (1.0 + (-v[d]).exp());
+                return if rng.gen::<f64>() < sigmoid {
+                    upper
+                } else {
+                    lower
+                };
+            }
+
+            let x = self.coordinates[d] + v[d];
+            if (lower..=upper).contains(&x) {
+                return x;
+            }
+            match settings.boundary {
+                Boundary::None => x,
+                Boundary::Clamp => {
+                    v[d] = 0.0;
+                    x.clamp(lower, upper)
+                }
+                Boundary::Reflect => {
+                    v[d] = -v[d];
+                    reflect(x, lower, upper)
+                }
+                Boundary::Reinitialise => {
+                    v[d] = 0.0;
+                    rng.gen_range(lower..=upper)
+                }
+                Boundary::Penalty => {
+                    outside = true;
+                    x
+                }
+            }
+        });
+
+        self.velocity = Vector::from(v);
+        self.coordinates = Vector::from(x);
+
+        outside
+    }
+
+    /// Takes the fitness and violation of the current position, remembering
+    /// it when it is better than the best one.
+    fn settle(
+        &mut self,
+        (fitness, violation): (f64, f64),
+        better: &impl Fn((f64, f64), (f64, f64)) -> bool,
+    ) {
+        (self.fitness, self.violation) = (fitness, violation);
+        if better(
+            (self.fitness, self.violation),
+            (self.best_fitness, self.best_violation),
+        ) {
+            self.remember();
+            self.best_fitness = self.fitness;
+            self.best_violation = self.violation;
+        }
+    }
+}
+
+/// Mirrors `x` back into `[lower, upper]`, folding repeatedly for steps
+/// larger than the range.
+fn reflect(x: f64, lower: f64, upper: f64) -> f64 {
+    let range = upper - lower;
+    let folded = (x - lower).rem_euclid(2.0 * range);
+    if folded <= range {
+        lower + folded
+    } else {
+        upper - (folded - range)
+    }
+}
+
+/// Per iteration record of a single pso run.
+#[derive(Debug, Default)]
+pub struct History {
+    /// global best fitness after each iteration
+    pub best: Vec<f64>,
+    /// mean distance of the particles to the swarm centre after each
+    /// iteration, never recorded in `mikes_pso::pso::pso` which keeps the
+    /// swarm to itself
+    pub diversity: Option<Vec<f64>>,
+    /// `(evaluations, fitness)` for every evaluation which improved on the
+    /// best fitness found so far
+    pub improvements: Vec<(usize, f64)>,
+}
+
+impl History {
+    pub fn new(diversity: bool) -> Self {
+        Self {
+            best: Vec::new(),
+            diversity: diversity.then(Vec::new),
+            improvements: Vec::new(),
+        }
+    }
+
+    fn record<const SIZE: usize>(&mut self, swarm: &[Particle<SIZE>], best: f64) {
+        self.best.push(best);
+        if let Some(diversity) = &mut self.diversity {
+            diversity.push(self::diversity(swarm));
+        }
+    }
+
+    /// Rows of `run, iteration, best, diversity`.
+    pub fn to_csv(&self, run: usize) -> String {
+        let mut csv = String::new();
+        for (i, best) in self.best.iter().enumerate() {
+            match &self.diversity {
+                Some(diversity) => csv.push_str(&format!("{run}, {i}, {best}, {}\n", diversity[i])),
+                None => csv.push_str(&format!("{run}, {i}, {best}, \n")),
+            }
+        }
+
+        csv
+    }
+}
+
+pub struct Run<const SIZE: usize> {
+    pub best: Vector<SIZE>,
+    /// number of function evaluations used
+    pub evaluations: usize,
+    pub history: History,
+}
+
+/// Mean euclidean distance from each particle to the centre of the swarm.
+pub fn diversity<const SIZE: usize>(swarm: &[Particle<SIZE>]) -> f64 {
+    let n = swarm.len() as f64;
+    let centre: [f64; SIZE] =
+        std::array::from_fn(|d| swarm.iter().map(|p| p.coordinates[d]).sum::<f64>() / n);
+
+    swarm
+        .iter()
+        .map(|p| {
+            (0..SIZE)
+                .map(|d| (p.coordinates[d] - centre[d]).powi(2))
+                .sum::<f64>()
+                .sqrt()
+        })
+        .sum::<f64>()
+        / n
+}
+
+/// Pso which records the best fitness (and optionally the swarm
+/// diversity) after every iteration.
+///
+/// The run is made by `mikes_pso::pso::pso` whenever it supports the
+/// settings and the strategy, see `Settings::upstream` and
+/// `VelocityStrategy::informed`, so that its results stay comparable with
+/// `results/`. Its history is recorded by observing every evaluation it
+/// makes, which leaves its search untouched: an iteration ends after every
+/// `particles` evaluations past the initial swarm, and an evaluation budget
+/// is turned into the iterations it pays for. The velocity strategies see
+/// the current particle and the best one, whose fitness upstream keeps to
+/// itself.
+///
+/// Otherwise the run is made by the swarm of this crate, where every
+/// particle is informed by the best particle of its neighbourhood in
+/// the topology of the settings, where the star topology uses the best
+/// position found so far. The velocity clamping and boundary policy of the
+/// settings are applied to every strategy after its update.
+///
+/// When the settings have an evaluation budget the run stops as soon as it
+/// is spent, even part way through an iteration, or after `IDLE` iterations
+/// in a row without an evaluation. Change detection costs an evaluation
+/// every iteration and a further one per particle on a change, and the
+/// particles the budget cannot re-evaluate forget their best position.
+///
+/// This swarm only exists for what upstream cannot do, and its results are
+/// not comparable with `results/`: particles start at rest uniformly within
+/// the bounds, every velocity of an iteration is computed from the swarm as
+/// it was at the start of the iteration before any particle moves, and the
+/// best particle informing the star topology is only updated at the end of
+/// every iteration.
+pub fn pso<const SIZE: usize>(
+    settings: &Settings,
+    bounds: &[Bound],
+    strategy: &(impl VelocityStrategy<SIZE> + ?Sized),
+    func: &impl Fn(&Vector<SIZE>) -> f64,
+) -> Run<SIZE> {
+    if settings.upstream() && !strategy.informed() {
+        upstream_pso(settings, bounds, strategy, func)
+    } else {
+        constrained_pso(settings, bounds, strategy, func, &|_| 0.0)
+    }
+}
+
+/// Pso on `function` like `pso`, where only the swarm of this crate handles
+/// constraints and evaluates a whole swarm in a single batch.
+pub fn optimise<const SIZE: usize>(
+    settings: &Settings,
+    bounds: &[Bound],
+    strategy: &(impl VelocityStrategy<SIZE> + ?Sized),
+    function: &Function<SIZE>,
+) -> Run<SIZE> {
+    if function.constraints.is_empty() && settings.upstream() && !strategy.informed() {
+        upstream_pso(settings, bounds, strategy, &function.func)
+    } else {
+        batched_pso(
+            settings,
+            bounds,
+            strategy,
+            &|positions| function.evaluate_batch(positions),
+            &|coords| function.violation(coords),
+        )
+    }
+}
+
+/// What `mikes_pso::pso::pso` has evaluated so far.
+struct Observer {
+    particles: usize,
+    evaluations: usize,
+    best: f64,
+    history: History,
+}
+
+impl Observer {
+    fn observe(&mut self, fitness: f64) {
+        self.evaluations += 1;
+        if fitness < self.best {
+            self.best = fitness;
+            self.history.improvements.push((self.evaluations, fitness));
+        }
+        if self.evaluations > self.particles
+            && (self.evaluations - self.particles).is_multiple_of(self.particles)
+        {
+            self.history.best.push(self.best);
+        }
+    }
+
+    /// Iteration the swarm is in, counting from zero after the initial
+    /// swarm.
+    fn iteration(&self) -> usize {
+        self.evaluations.saturating_sub(self.particles) / self.particles
+    }
+}
+
+/// Runs `mikes_pso::pso::pso`, recording the history of `pso` from every
+/// evaluation it makes.
+fn upstream_pso<const SIZE: usize>(
+    settings: &Settings,
+    bounds: &[Bound],
+    strategy: &(impl VelocityStrategy<SIZE> + ?Sized),
+    func: &impl Fn(&Vector<SIZE>) -> f64,
+) -> Run<SIZE> {
+    let iterations = match settings.budget(SIZE) {
+        Some(evaluations) => evaluations.saturating_sub(settings.particles) / settings.particles,
+        None => settings.iterations,
+    };
+    let observer = RefCell::new(Observer {
+        particles: settings.particles,
+        evaluations: 0,
+        best: f64::INFINITY,
+        history: History::new(false),
+    });
+    let observed = |coords: &Vector<SIZE>| {
+        let fitness = func(coords);
+        observer.borrow_mut().observe(fitness);
+        fitness
+    };
+    let velocity = |current: &_, best: &_| {
+        let (current, best) = (Particle::upstream(current), Particle::upstream(best));
+        let context = Context {
+            iteration: observer.borrow().iteration(),
+            iterations,
+            neighbours: &[&current, &best],
+        };
+        strategy.update(&current, &best, &context)
+    };
+
+    let particle = mikes_pso::pso::pso(settings.particles, iterations, bounds, velocity, &observed);
+    let observer = observer.into_inner();
+    Run {
+        best: particle.coordinates(),
+        evaluations: observer.evaluations,
+        history: observer.history,
+    }
+}
+
+/// Pso on a constrained function, comparing particles by their fitness and
+/// their `violation` of the constraints under the handling of the settings.
+/// Only feasible evaluations are recorded as improvements.
+pub fn constrained_pso<const SIZE: usize>(
+    settings: &Settings,
+    bounds: &[Bound],
+    strategy: &(impl VelocityStrategy<SIZE> + ?Sized),
+    func: &impl Fn(&Vector<SIZE>) -> f64,
+    violation: &impl Fn(&Vector<SIZE>) -> f64,
+) -> Run<SIZE> {
+    batched_pso(
+        settings,
+        bounds,
+        strategy,
+        &|positions: &[Vector<SIZE>]| positions.iter().map(func).collect(),
+        violation,
+    )
+}
+
+/// Constrained pso which evaluates the positions of every particle due an
+/// evaluation in a single call of `batch`, once for the initial swarm and
+/// once per iteration, giving the same run as evaluating them one at a time.
+pub fn batched_pso<const SIZE: usize>(
+    settings: &Settings,
+    bounds: &[Bound],
+    strategy: &(impl VelocityStrategy<SIZE> + ?Sized),
+    batch: &impl Fn(&[Vector<SIZE>]) -> Vec<f64>,
+    violation: &impl Fn(&Vector<SIZE>) -> f64,
+) -> Run<SIZE> {
+    let counted = Counted::new(batch);
+    let improvements = RefCell::new(Vec::<(usize, f64)>::new());
+    let func = |positions: &[Vector<SIZE>]| {
+        let before = counted.evaluations();
+        let mut improvements = improvements.borrow_mut();
+        positions
+            .iter()
+            .zip(counted.evaluate(positions))
+            .enumerate()
+            .map(|(i, (coords, fitness))| {
+                let violation = violation(coords);
+                if violation == 0.0 && improvements.last().is_none_or(|(_, best)| fitness < *best) {
+                    improvements.push((before + i + 1, fitness));
+                }
+                (fitness, violation)
+            })
+            .collect::<Vec<_>>()
+    };
+    let budget = settings.budget(SIZE);
+    // evaluations left in the budget
+    let remaining = || {
+        budget.map_or(usize::MAX, |evaluations| {
+            evaluations.saturating_sub(counted.evaluations())
+        })
+    };
+    let spent = || remaining() == 0;
+    // the expected number of iterations is only used to report progress to the
+    // strategies, the budget is the actual stopping condition
+    let iterations = match budget {
+        Some(evaluations) => evaluations.saturating_sub(settings.particles) / settings.particles,
+        None => settings.iterations,
+    };
+
+    let positions = (0..settings.particles)
+        .map(|_| Particle::place(bounds, settings))
+        .collect::<Vec<_>>();
+    let mut swarm = positions
+        .iter()
+        .zip(func(&positions))
+        .map(|(&coordinates, evaluation)| Particle::new(coordinates, evaluation))
+        .collect::<Vec<_>>();
+    // the first initial position, whose fitness only moves on a change
+    let mut sentinel = (positions[0], swarm[0].fitness);
+    let better = |x, y| settings.handling.better(x, y, 0, iterations);
+    let mut best = best_of(&swarm, &better).clone();
+    let mut history = History::new(settings.diversity);
+    let mut neighbourhoods = settings.topology.neighbourhoods(swarm.len());
+
+    let mut iteration = 0;
+    let mut idle = 0;
+    while !spent() && idle < IDLE && (budget.is_some() || iteration < iterations) {
+        let evaluations = counted.evaluations();
+        let better = |x, y| settings.handling.better(x, y, iteration, iterations);
+        let changed = settings.detection && {
+            let fitness = func(&[sentinel.0])[0].0;
+            let changed = (fitness - sentinel.1).abs() > TOLERANCE * sentinel.1.abs().max(1.0);
+            sentinel.1 = fitness;
+            changed
+        };
+        if changed {
+            // the best positions are evaluated again after the change, and
+            // those beyond the budget are forgotten
+            let evaluated = swarm.len().min(remaining());
+            let positions = swarm[..evaluated]
+                .iter()
+                .map(|particle| particle.best)
+                .collect::<Vec<_>>();
+            let evaluations = func(&positions)
+                .into_iter()
+                .chain(std::iter::repeat((f64::INFINITY, f64::INFINITY)));
+            for (particle, evaluation) in swarm.iter_mut().zip(evaluations) {
+                (particle.best_fitness, particle.best_violation) = evaluation;
+            }
+            best = best_of(&swarm, &better).clone();
+        }
+
+        let velocities = swarm
+            .iter()
+            .zip(&neighbourhoods)
+            .map(|(particle, neighbourhood)| {
+                let neighbours = neighbourhood.iter().map(|&j| &swarm[j]).collect::<Vec<_>>();
+                let context = Context {
+                    iteration,
+                    iterations,
+                    neighbours: &neighbours,
+                };
+                match settings.topology {
+                    Topology::Star => strategy.update(particle, &best, &context),
+                    _ => strategy.update(
+                        particle,
+                        best_of(neighbours.iter().copied(), &better),
+                        &context,
+                    ),
+                }
+            })
+            .collect::<Vec<_>>();
+        // particles outside the bounds under the penalty policy are not
+        // evaluated, and those beyond the budget do not move
+        let mut due = Vec::with_capacity(swarm.len());
+        for (i, (particle, v)) in swarm.iter_mut().zip(velocities).enumerate() {
+            if due.len() >= remaining() {
+                break;
+            }
+            if particle.fly(v, bounds, settings) {
+                particle.settle((f64::INFINITY, f64::INFINITY), &better);
+            } else {
+                due.push(i);
+            }
+        }
+        let positions = due
+            .iter()
+            .map(|&i| swarm[i].coordinates)
+            .collect::<Vec<_>>();
+        for (i, evaluation) in due.into_iter().zip(func(&positions)) {
+            swarm[i].settle(evaluation, &better);
+        }
+
+        let candidate = best_of(&swarm, &better);
+        if better(
+            (candidate.best_fitness, candidate.best_violation),
+            (best.best_fitness, best.best_violation),
+        ) {
+            best = candidate.clone();
+        } else if let Topology::Random(_) = settings.topology {
+            neighbourhoods = settings.topology.neighbourhoods(swarm.len());
+        }
+        history.record(&swarm, best.best_fitness);
+        iteration += 1;
+        if counted.evaluations() == evaluations {
+            idle += 1;
+        } else {
+            idle = 0;
+        }
+    }
+
+    history.improvements = improvements.into_inner();
+    Run {
+        best: best.best,
+        evaluations: counted.evaluations(),
+        history,
+    }
+}
+
+fn best_of<'a, const SIZE: usize>(
+    swarm: impl IntoIterator<Item = &'a Particle<SIZE>>,
+    better: &impl Fn((f64, f64), (f64, f64)) -> bool,
+) -> &'a Particle<SIZE> {
+    swarm
+        .into_iter()
+        .reduce(|best, particle| {
+            if better(
+                (particle.best_fitness, particle.best_violation),
+                (best.best_fitness, best.best_violation),
+            ) {
+                particle
+            } else {
+                best
+            }
+        })
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A particle at 0.5 in `[0, 1]` moved by `velocity` under `boundary`.
+    fn flown(boundary: Boundary, velocity: f64) -> (Particle<1>, bool) {
+        let mut particle = Particle::at(Vector::from([0.5]));
+        let settings = Settings {
+            boundary,
+            ..Settings::default()
+        };
+        let outside = particle.fly(
+            Vector::from([velocity]),
+            &[Bound::from((0.0, 1.0))],
+            &settings,
+        );
+        (particle, outside)
+    }
+
+    #[test]
+    fn reflection() {
+        assert_eq!(reflect(1.25, 0.0, 1.0), 0.75);
+        assert_eq!(reflect(-0.25, 0.0, 1.0), 0.25);
+        // steps larger than the range fold more than once
+        assert_eq!(reflect(2.25, 0.0, 1.0), 0.25);
+        assert_eq!(reflect(-1.5, 0.0, 1.0), 0.5);
+        assert_eq!(reflect(3.5, 1.0, 2.0), 1.5);
+    }
+
+    #[test]
+    fn boundaries() {
+        let (particle, outside) = flown(Boundary::None, 0.75);
+        assert_eq!(
+            (particle.coordinates[0], particle.velocity[0]),
+            (1.25, 0.75)
+        );
+        assert!(!outside);
+
+        let (particle, _) = flown(Boundary::Clamp, 0.75);
+        assert_eq!((particle.coordinates[0], particle.velocity[0]), (1.0, 0.0));
+        let (particle, _) = flown(Boundary::Clamp, -0.75);
+        assert_eq!((particle.coordinates[0], particle.velocity[0]), (0.0, 0.0));
+
+        let (particle, _) = flown(Boundary::Reflect, 0.75);
+        assert_eq!(
+            (particle.coordinates[0], particle.velocity[0]),
+            (0.75, -0.75)
+        );
+        let (particle, _) = flown(Boundary::Reflect, -1.75);
+        assert_eq!(
+            (particle.coordinates[0], particle.velocity[0]),
+            (0.75, 1.75)
+        );
+
+        for _ in 0..100 {
+            let (particle, _) = flown(Boundary::Reinitialise, 0.75);
+            assert!((0.0..=1.0).contains(&particle.coordinates[0]));
+            assert_eq!(particle.velocity[0], 0.0);
+        }
+
+        let (particle, outside) = flown(Boundary::Penalty, 0.75);
+        assert_eq!(particle.coordinates[0], 1.25);
+        assert!(outside);
+    }
+
+    #[test]
+    fn inside() {
+        // components within the bounds are never touched by the policy
+        for boundary in [
+            Boundary::Clamp,
+            Boundary::Reflect,
+            Boundary::Reinitialise,
+            Boundary::Penalty,
+        ] {
+            let (particle, outside) = flown(boundary, 0.25);
+            assert_eq!(
+                (particle.coordinates[0], particle.velocity[0]),
+                (0.75, 0.25)
+            );
+            assert!(!outside);
+        }
+    }
+
+    #[test]
+    fn feasibility() {
+        let better = |x, y| Handling::Feasibility.better(x, y, 0, 10);
+        // feasible beats infeasible however good the fitness
+        assert!(better((5.0, 0.0), (1.0, 0.1)));
+        assert!(!better((1.0, 0.1), (5.0, 0.0)));
+        // two feasible by fitness and two infeasible by violation
+        assert!(better((1.0, 0.0), (2.0, 0.0)));
+        assert!(better((5.0, 0.1), (1.0, 0.2)));
+        // equal violations by fitness
+        assert!(better((1.0, 0.3), (2.0, 0.3)));
+        assert!(!better((1.0, 0.0), (1.0, 0.0)));
+    }
+
+    #[test]
+    fn epsilon() {
+        let epsilon = Handling::Epsilon(0.64);
+        // at the start every violation up to 0.64 is feasible
+        assert!(epsilon.better((1.0, 0.5), (2.0, 0.0), 0, 10));
+        assert!(!epsilon.better((1.0, 0.7), (2.0, 0.0), 0, 10));
+        // halfway to 80% of the iterations the level is 0.64 / 32 = 0.02
+        assert!(epsilon.better((1.0, 0.01), (2.0, 0.0), 4, 10));
+        assert!(!epsilon.better((1.0, 0.03), (2.0, 0.0), 4, 10));
+        // and it is zero from then on
+        assert!(!epsilon.better((1.0, 1e-9), (2.0, 0.0), 8, 10));
+        assert!(!epsilon.better((1.0, 1e-9), (2.0, 0.0), 10, 10));
+    }
+
+    #[test]
+    fn penalty() {
+        let penalty = Handling::Penalty(10.0);
+        assert!(penalty.better((1.0, 0.1), (2.5, 0.0), 0, 10));
+        assert!(!penalty.better((1.0, 0.2), (2.5, 0.0), 0, 10));
+    }
+
+    #[test]
+    fn clamping() {
+        let mut particle = Particle::at(Vector::from([0.5]));
+        let settings = Settings {
+            vmax: Some(0.1),
+            boundary: Boundary::Clamp,
+            ..Settings::default()
+        };
+        particle.fly(Vector::from([-1.0]), &[Bound::from((0.0, 2.0))], &settings);
+        assert_eq!(particle.velocity[0], -0.2);
+        assert_eq!(particle.coordinates[0], 0.3);
+    }
+}
@@ -0,0 +1,116 @@
+use rand::Rng;
+
+/// Neighbourhood structure of the swarm which decides which particle is the
+/// `best` passed to the velocity update of each particle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    /// every particle is informed by the global best
+    Star,
+    /// every particle is informed by its left and right neighbour
+    Ring,
+    /// particles lie on a wrapping grid and are informed by the particles
+    /// above, below, left and right of them
+    VonNeumann,
+    /// every particle informs itself and `k` random particles, redrawn
+    /// whenever an iteration fails to improve the global best
+    Random(usize),
+}
+
+impl Topology {
+    /// Indices of the neighbourhood of every particle, each including the
+    /// particle itself.
+    pub fn neighbourhoods(&self, particles: usize) -> Vec<Vec<usize>> {
+        let n = particles;
+        match self {
+            Topology::Star => vec![(0..n).collect(); n],
+            Topology::Ring => (0..n)
+                .map(|i| dedup(vec![i, (i + n - 1) % n, (i + 1) % n]))
+                .collect(),
+            Topology::VonNeumann => {
+                let columns = (n as f64).sqrt().ceil() as usize;
+                (0..n)
+                    .map(|i| {
+                        let (row, column) = (i / columns, i % columns);
+                        let rows = n.div_ceil(columns);
+                        let at = |row: usize, column: usize| (row * columns + column) % n;
+                        dedup(vec![
+                            i,
+                            at((row + rows - 1) % rows, column),
+                            at((row + 1) % rows, column),
+                            at(row, (column + columns - 1) % columns),
+                            at(row, (column + 1) % columns),
+                        ])
+                    })
+                    .collect()
+            }
+            Topology::Random(k) => {
+                let mut rng = rand::thread_rng();
+                let mut neighbourhoods = (0..n).map(|i| vec![i]).collect::<Vec<_>>();
+                for j in 0..n {
+                    for _ in 0..*k {
+                        neighbourhoods[rng.gen_range(0..n)].push(j);
+                    }
+                }
+                neighbourhoods.into_iter().map(dedup).collect()
+            }
+        }
+    }
+}
+
+fn dedup(mut indices: Vec<usize>) -> Vec<usize> {
+    indices.sort_unstable();
+    indices.dedup();
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn star() {
+        assert_eq!(Topology::Star.neighbourhoods(3), vec![vec![0, 1, 2]; 3]);
+    }
+
+    #[test]
+    fn ring() {
+        assert_eq!(
+            Topology::Ring.neighbourhoods(4),
+            vec![vec![0, 1, 3], vec![0, 1, 2], vec![1, 2, 3], vec![0, 2, 3]]
+        );
+        // the neighbours of a pair of particles are each other
+        assert_eq!(Topology::Ring.neighbourhoods(2), vec![vec![0, 1]; 2]);
+    }
+
+    #[test]
+    fn von_neumann() {
+        // a 3 by 3 grid
+        let neighbourhoods = Topology::VonNeumann.neighbourhoods(9);
+        assert_eq!(neighbourhoods[0], vec![0, 1, 2, 3, 6]);
+        assert_eq!(neighbourhoods[4], vec![1, 3, 4, 5, 7]);
+        assert_eq!(neighbourhoods[8], vec![2, 5, 6, 7, 8]);
+        // every particle is in the neighbourhood of each of its neighbours
+        for (i, neighbourhood) in neighbourhoods.iter().enumerate() {
+            for &j in neighbourhood {
+                assert!(neighbourhoods[j].contains(&i));
+            }
+        }
+    }
+
+    #[test]
+    fn random() {
+        let k = 3;
+        let neighbourhoods = Topology::Random(k).neighbourhoods(20);
+        assert_eq!(neighbourhoods.len(), 20);
+        for (i, neighbourhood) in neighbourhoods.iter().enumerate() {
+            assert!(neighbourhood.contains(&i));
+            assert!(neighbourhood.windows(2).all(|pair| pair[0] < pair[1]));
+            assert!(neighbourhood.iter().all(|&j| j < 20));
+        }
+        // every particle informs itself and at most `k` others
+        for j in 0..20 {
+            let informed = neighbourhoods.iter().filter(|n| n.contains(&j)).count();
+            assert!((1..=k + 1).contains(&informed));
+        }
+    }
+}
@@ -1,6 +1,11 @@
+use crate::evolver::Expression;
+use crate::function::Function;
+use crate::strategy::{Context, VelocityStrategy};
+use crate::swarm::{optimise, Particle, Settings};
 use mikes_ge::grammar::Grammar;
-use mikes_pso::{bounds::Bound, particle::Particle, pso::pso, vector::Vector};
+use mikes_pso::{bounds::Bound, vector::Vector};
 use rand::Rng;
+use std::fmt::{self, Display};
 use std::marker::PhantomData;
 
 #[derive(Debug)]
@@ -17,6 +22,10 @@ pub enum Velocity<'a, const SIZE: usize> {
     _Unused(PhantomData<&'a ()>),
 }
 
+pub const COGNITIVE: f64 = 0.3;
+pub const SOCIAL: f64 = 0.3;
+pub const INERTIA_WEIGHT: f64 = 0.5;
+
 #[derive(Debug)]
 pub enum ScalarOps {
     Cognitive,
@@ -29,13 +38,12 @@ pub enum ScalarOps {
 }
 
 impl<'a, const SIZE: usize> Grammar for Velocity<'a, SIZE> {
-    type Input = (&'a Box<dyn Fn(&Vector<SIZE>) -> f64 + Send>, &'a [Bound]);
+    type Input = (&'a Function<SIZE>, &'a [Bound], Settings);
     type Output = f64;
 
     fn run(&self, input: &Self::Input) -> Self::Output {
-        let func = |current: &_, best: &_| self.runner(current, best);
-        let particle = pso(100, 100, input.1, func, &input.0);
-        (input.0)(&particle.coordinates())
+        let run = optimise(&input.2, input.1, self, input.0);
+        input.0.true_fitness(&run.best)
     }
 
     fn generate(chromosome: &[u8]) -> Self {
@@ -87,12 +95,81 @@ impl<'a, const SIZE: usize> Velocity<'a, SIZE> {
     }
 }
 
+impl<'a, const SIZE: usize> VelocityStrategy<SIZE> for Velocity<'a, SIZE> {
+    fn name(&self) -> String {
+        "evolved".to_string()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        vec![("c1", COGNITIVE), ("c2", SOCIAL), ("w", INERTIA_WEIGHT)]
+    }
+
+    fn update(
+        &self,
+        current: &Particle<SIZE>,
+        best: &Particle<SIZE>,
+        _: &Context<SIZE>,
+    ) -> Vector<SIZE> {
+        self.runner(current, best)
+    }
+}
+
+impl<'a, const SIZE: usize> Display for Velocity<'a, SIZE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Velocity::CurrentCoords => write!(f, "x"),
+            Velocity::BestCoords => write!(f, "x_g"),
+            Velocity::CurrentBestCoords => write!(f, "p"),
+            Velocity::BestBestCoords => write!(f, "p_g"),
+            Velocity::CurrentVelocity => write!(f, "v"),
+            Velocity::BestVelocity => write!(f, "v_g"),
+            Velocity::Mul(x, y) => write!(f, "({x} * {y})"),
+            Velocity::Add(x, y) => write!(f, "({x} + {y})"),
+            Velocity::Sub(x, y) => write!(f, "({x} - {y})"),
+            Velocity::_Unused(_) => panic!("Cannot get here"),
+        }
+    }
+}
+
+impl<'a, const SIZE: usize> Expression for Velocity<'a, SIZE> {
+    fn size(&self) -> usize {
+        match self {
+            Velocity::Mul(x, y) => 1 + x.size() + y.size(),
+            Velocity::Add(x, y) | Velocity::Sub(x, y) => 1 + x.size() + y.size(),
+            _ => 1,
+        }
+    }
+}
+
+impl Display for ScalarOps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScalarOps::Cognitive => write!(f, "c1"),
+            ScalarOps::Social => write!(f, "c2"),
+            ScalarOps::InertiaWeight => write!(f, "w"),
+            ScalarOps::Rand => write!(f, "r"),
+            ScalarOps::Mul(x, y) => write!(f, "({x} * {y})"),
+            ScalarOps::Add(x, y) => write!(f, "({x} + {y})"),
+            ScalarOps::Sub(x, y) => write!(f, "({x} - {y})"),
+        }
+    }
+}
+
 impl ScalarOps {
+    fn size(&self) -> usize {
+        match self {
+            ScalarOps::Mul(x, y) | ScalarOps::Add(x, y) | ScalarOps::Sub(x, y) => {
+                1 + x.size() + y.size()
+            }
+            _ => 1,
+        }
+    }
+
     fn runner(&self) -> f64 {
         match self {
-            ScalarOps::Cognitive => 0.3,
-            ScalarOps::Social => 0.3,
-            ScalarOps::InertiaWeight => 0.5,
+            ScalarOps::Cognitive => COGNITIVE,
+            ScalarOps::Social => SOCIAL,
+            ScalarOps::InertiaWeight => INERTIA_WEIGHT,
             ScalarOps::Rand => rand::thread_rng().gen(),
             ScalarOps::Mul(x, y) => x.runner() * y.runner(),
             ScalarOps::Add(x, y) => x.runner() + y.runner(),